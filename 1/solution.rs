@@ -1,69 +1,683 @@
 // Advent of code: Day 1
 // Author: @alisinabh
 
-use std::env;
-use std::fs::read_to_string;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1]).unwrap();
+use crate::automaton::Automaton;
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+use crate::table;
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = String;
+    type Output = i32;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part_one(document: &String) -> Result<i32, Box<dyn Error>> {
+        Ok(calibrate(document, Calibration::Numerals).sum)
+    }
+
+    fn part_two(document: &String) -> Result<i32, Box<dyn Error>> {
+        let vocabulary = Vocabulary::english();
+        let mode = Calibration::NumeralsAndWords(&vocabulary);
+
+        Ok(calibrate(document, mode).sum)
+    }
+}
+
+/// Runs a part like `Day1` does, but reading spelled digits from the given
+/// vocabulary and handing back the skipped lines for the caller to show.
+pub fn solve(
+    part: u8,
+    document: &str,
+    vocabulary: &Vocabulary,
+) -> Result<Calibrated, Box<dyn Error>> {
+    Ok(calibrate(
+        document,
+        Calibration::for_part(part, vocabulary)?,
+    ))
+}
+
+/// Which spellings of a digit count when recovering calibration values.
+#[derive(Debug, Clone, Copy)]
+pub enum Calibration<'a> {
+    /// Only numerals, as in part one.
+    Numerals,
+    /// Numerals and the words of a vocabulary, as in part two.
+    NumeralsAndWords(&'a Vocabulary),
+}
+
+impl<'a> Calibration<'a> {
+    pub fn for_part(part: u8, vocabulary: &'a Vocabulary) -> Result<Self, Box<dyn Error>> {
+        match part {
+            1 => Ok(Calibration::Numerals),
+            2 => Ok(Calibration::NumeralsAndWords(vocabulary)),
+            p => Err(format!("invalid part {}", p).into()),
+        }
+    }
+}
+
+/// Sum of the calibration values along with a warning for every line that
+/// was left out of it, for having no digit or one written in another script.
+#[derive(Debug, Clone)]
+pub struct Calibrated {
+    pub sum: i32,
+    pub warnings: Vec<ParseError>,
+}
+
+pub fn calibrate(document: &str, mode: Calibration) -> Calibrated {
     let mut sum: i32 = 0;
+    let mut warnings = Vec::new();
+
+    for line in error::lines(document) {
+        let explanation = explain_line(&line, mode);
+
+        match (explanation.value(), explanation.warning()) {
+            (Some(value), _) => sum += value,
+            (None, Some(warning)) => warnings.push(warning),
+            (None, None) => {}
+        }
+    }
+
+    Calibrated { sum, warnings }
+}
+
+/// How a line's calibration value was put together, for every line.
+pub fn explain<'a>(document: &'a str, mode: Calibration) -> Vec<Explanation<'a>> {
+    error::lines(document)
+        .map(|line| explain_line(&line, mode))
+        .collect()
+}
+
+/// Whether a digit was written as a numeral or spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Numeral,
+    Word,
+}
+
+impl Source {
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Numeral => "numeral",
+            Source::Word => "word",
+        }
+    }
+}
+
+/// A digit found in a line, spanning the bytes `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digit {
+    pub value: u32,
+    pub start: usize,
+    pub end: usize,
+    pub source: Source,
+}
+
+/// The first and last digit of a line, if it has any. Lines with a digit
+/// of another script have none, as their value can't be trusted.
+#[derive(Debug, Clone, Copy)]
+pub struct Explanation<'a> {
+    pub line: Line<'a>,
+    pub digits: Option<(Digit, Digit)>,
+    /// Byte offset and character of the first digit of another script.
+    pub unsupported: Option<(usize, char)>,
+}
+
+impl Explanation<'_> {
+    pub fn value(&self) -> Option<i32> {
+        let (first, last) = self.digits?;
+        Some((first.value * 10 + last.value) as i32)
+    }
+
+    /// Why the line has no value, if it has none.
+    pub fn warning(&self) -> Option<ParseError> {
+        let line = &self.line;
+
+        if let Some((i, c)) = self.unsupported {
+            let at = &line.text[i..i + c.len_utf8()];
+            return Some(line.error(ParseErrorKind::InvalidCharacter(c), at));
+        }
+
+        self.digits
+            .is_none()
+            .then(|| line.error(ParseErrorKind::Expected("a digit"), line.text))
+    }
+
+    /// 1-based character column of a digit of this line.
+    pub fn column(&self, digit: &Digit) -> usize {
+        self.line.text[..digit.start].chars().count() + 1
+    }
+}
+
+/// Table of explanations, one row per line.
+pub struct Explained<'a>(pub &'a [Explanation<'a>]);
+
+impl fmt::Display for Explained<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            "line", "value", "first", "column", "from", "last", "column", "from",
+        ];
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|e| {
+                let mut row = vec![e.line.number.to_string()];
+
+                match (e.value(), e.digits) {
+                    (Some(value), Some((first, last))) => {
+                        row.push(value.to_string());
+
+                        for digit in [first, last] {
+                            row.push(e.line.text[digit.start..digit.end].to_string());
+                            row.push(e.column(&digit).to_string());
+                            row.push(digit.source.name().to_string());
+                        }
+                    }
+                    _ => {
+                        row.push(match e.unsupported {
+                            Some((_, c)) => format!("unsupported {}", c),
+                            None => "no digit".to_string(),
+                        });
+                        row.extend(std::iter::repeat_n("-".to_string(), 6));
+                    }
+                }
+
+                row
+            })
+            .collect();
+
+        write!(f, "{}", table::render(&header, &rows))
+    }
+}
+
+const ENGLISH: &[(&str, u32)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const GERMAN: &[(&str, u32)] = &[
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+/// Words that spell out a digit, e.g. "one" for 1.
+///
+/// Written as comma separated entries, each either `token=digit` or the name
+/// of a built-in list (`english`, `german`), so `german,null=0` reads German
+/// digits plus zero. All words are searched for at once, so a line is scanned
+/// a single time however large the vocabulary is.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    automaton: Automaton,
+}
+
+impl Vocabulary {
+    pub fn new(words: Vec<(String, u32)>) -> Result<Self, Box<dyn Error>> {
+        let mut unique: Vec<(String, u32)> = Vec::with_capacity(words.len());
+
+        for (word, digit) in words {
+            if word.is_empty() {
+                return Err("empty word in digit vocabulary".into());
+            }
+
+            if digit > 9 {
+                return Err(format!("`{}` must spell a single digit, not {}", word, digit).into());
+            }
+
+            match unique.iter().find(|(w, _)| *w == word) {
+                Some(&(_, d)) if d != digit => {
+                    return Err(format!("`{}` spells both {} and {}", word, d, digit).into())
+                }
+                Some(_) => {}
+                None => unique.push((word, digit)),
+            }
+        }
+
+        let automaton = Automaton::new(unique.iter().map(|(word, _)| word));
+
+        Ok(Vocabulary {
+            words: unique,
+            automaton,
+        })
+    }
+
+    pub fn english() -> Self {
+        Self::preset(ENGLISH)
+    }
+
+    pub fn german() -> Self {
+        Self::preset(GERMAN)
+    }
+
+    fn preset(words: &[(&str, u32)]) -> Self {
+        let words = words.iter().map(|&(w, d)| (w.to_string(), d)).collect();
+        Self::new(words).expect("built-in vocabularies are valid")
+    }
+
+    pub fn words(&self) -> &[(String, u32)] {
+        &self.words
+    }
+
+    /// Every word in the line, overlaps included.
+    fn find<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Digit> + 'a {
+        self.automaton.find_overlapping(line).map(|m| Digit {
+            value: self.words[m.pattern].1,
+            start: m.start,
+            end: m.end,
+            source: Source::Word,
+        })
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Vec::new();
 
-    for line in file.lines() {
-        sum = sum + extract_value(line).unwrap();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let preset = match entry {
+                "english" => ENGLISH,
+                "german" => GERMAN,
+                _ => {
+                    let Some((word, digit)) = entry.split_once('=') else {
+                        return Err(format!(
+                            "unknown vocabulary {}, expected english, german or word=digit",
+                            entry
+                        )
+                        .into());
+                    };
+
+                    let digit = digit
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("`{}` is not a digit", digit.trim()))?;
+                    words.push((word.trim().to_string(), digit));
+                    continue;
+                }
+            };
+
+            words.extend(preset.iter().map(|&(w, d)| (w.to_string(), d)));
+        }
+
+        if words.is_empty() {
+            return Err("empty digit vocabulary".into());
+        }
+
+        Vocabulary::new(words)
+    }
+}
+
+fn explain_line<'a>(line: &Line<'a>, mode: Calibration) -> Explanation<'a> {
+    let unsupported = unsupported_digit(line.text);
+
+    Explanation {
+        line: *line,
+        digits: match unsupported {
+            Some(_) => None,
+            None => first_and_last_digit(line.text, mode),
+        },
+        unsupported,
     }
+}
+
+/// Zero of every run of ten decimal digits outside ASCII, such as '٠' or
+/// '０', making up the `Nd` category of Unicode up to version 17. Other
+/// numeric characters like '²', '½' or 'Ⅻ' aren't digits of a number and are
+/// ignored. A test checks this against `char::is_numeric` so it can't fall
+/// behind the Unicode version of the toolchain unnoticed.
+const DECIMAL_ZEROS: &[u32] = &[
+    0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6, 0x0D66,
+    0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x10D30, 0x10D40, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50, 0x11D50,
+    0x11DA0, 0x11DE0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50, 0x16D70, 0x1CCF0, 0x1D7CE,
+    0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950, 0x1FBF0,
+];
 
-    println!("The sum is {}", sum);
+/// A decimal digit other than the ASCII numerals, such as '٣' or '７'.
+/// These would otherwise be skipped and quietly change the value of a line.
+fn unsupported_digit(line: &str) -> Option<(usize, char)> {
+    line.char_indices().find(|&(_, c)| {
+        let code = c as u32;
+        let run = DECIMAL_ZEROS.partition_point(|&zero| zero <= code);
+
+        run > 0 && code - DECIMAL_ZEROS[run - 1] < 10
+    })
 }
 
-fn extract_value(line: &str) -> Result<i32, std::num::ParseIntError> {
-    let line = replace_literal_digits(line);
-    let mut chars = line.chars();
+/// Every digit in the line, written as a numeral or, when the mode allows
+/// it, spelled out. Words are matched in place rather than consumed, so the
+/// letters of one word can still start the next one as in "eightwo".
+fn digits<'a>(line: &'a str, mode: Calibration<'a>) -> impl Iterator<Item = Digit> + 'a {
+    let numerals = line.char_indices().filter_map(|(i, c)| {
+        Some(Digit {
+            value: c.to_digit(10)?,
+            start: i,
+            end: i + c.len_utf8(),
+            source: Source::Numeral,
+        })
+    });
 
-    let first_digit = chars.find(|c| c.is_digit(10)).unwrap();
-    let last_digit = if let Some(c) = chars.rev().find(|c| c.is_digit(10)) {
-        c
-    } else {
-        first_digit
+    let words = match mode {
+        Calibration::Numerals => None,
+        Calibration::NumeralsAndWords(vocabulary) => Some(vocabulary.find(line)),
     };
 
-    let number_str = format!("{}{}", first_digit, last_digit);
-
-    number_str.parse()
-}
-
-fn replace_literal_digits(line: &str) -> String {
-    let mut normal_line = String::new();
-    let mut i = 0;
-    let chars_count = line.len();
-
-    while i < chars_count {
-        let char_and_len: (char, usize) = if line[i..].starts_with("one") {
-            ('1', "one".len())
-        } else if line[i..].starts_with("two") {
-            ('2', "two".len())
-        } else if line[i..].starts_with("three") {
-            ('3', "three".len())
-        } else if line[i..].starts_with("four") {
-            ('4', "four".len())
-        } else if line[i..].starts_with("five") {
-            ('5', "five".len())
-        } else if line[i..].starts_with("six") {
-            ('6', "six".len())
-        } else if line[i..].starts_with("seven") {
-            ('7', "seven".len())
-        } else if line[i..].starts_with("eight") {
-            ('8', "eight".len())
-        } else if line[i..].starts_with("nine") {
-            ('9', "nine".len())
-        } else {
-            (line.chars().nth(i).unwrap(), 1)
-        };
-
-        normal_line.push(char_and_len.0);
-        i = i + char_and_len.1;
-    }
-
-    normal_line
+    numerals.chain(words.into_iter().flatten())
+}
+
+/// First and last digit of the line, found in a single scan. When two words
+/// start at the same place the longer one wins.
+fn first_and_last_digit(line: &str, mode: Calibration) -> Option<(Digit, Digit)> {
+    let mut first: Option<Digit> = None;
+    let mut last: Option<Digit> = None;
+
+    for digit in digits(line, mode) {
+        let (start, end) = (digit.start, digit.end);
+
+        if first.is_none_or(|d| start < d.start || (start == d.start && end > d.end)) {
+            first = Some(digit);
+        }
+
+        if last.is_none_or(|d| start > d.start || (start == d.start && end > d.end)) {
+            last = Some(digit);
+        }
+    }
+
+    Some((first?, last?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(text: &str) -> i32 {
+        let vocabulary = Vocabulary::english();
+        let mode = Calibration::NumeralsAndWords(&vocabulary);
+
+        explain_line(&Line { number: 1, text }, mode)
+            .value()
+            .unwrap()
+    }
+
+    fn value_in(spec: &str, text: &str) -> i32 {
+        let vocabulary: Vocabulary = spec.parse().unwrap();
+        let mode = Calibration::NumeralsAndWords(&vocabulary);
+
+        explain_line(&Line { number: 1, text }, mode)
+            .value()
+            .unwrap()
+    }
+
+    fn numerals_value(text: &str) -> Option<i32> {
+        explain_line(&Line { number: 1, text }, Calibration::Numerals).value()
+    }
+
+    #[test]
+    fn example_lines() {
+        assert_eq!(value("two1nine"), 29);
+        assert_eq!(value("eightwothree"), 83);
+        assert_eq!(value("abcone2threexyz"), 13);
+        assert_eq!(value("xtwone3four"), 24);
+        assert_eq!(value("4nineeightseven2"), 42);
+        assert_eq!(value("zoneight234"), 14);
+        assert_eq!(value("7pqrstsixteen"), 76);
+    }
+
+    #[test]
+    fn numerals_only_ignores_words() {
+        assert_eq!(numerals_value("1abc2"), Some(12));
+        assert_eq!(numerals_value("pqr3stu8vwx"), Some(38));
+        assert_eq!(numerals_value("a1b2c3d4e5f"), Some(15));
+        assert_eq!(numerals_value("treb7uchet"), Some(77));
+        assert_eq!(numerals_value("two1nine"), Some(11));
+        assert_eq!(numerals_value("eightwothree"), None);
+    }
+
+    #[test]
+    fn modes_share_the_document_sum() {
+        let document = "two1nine\n4nineeightseven2\n";
+        let vocabulary = Vocabulary::english();
+
+        let numerals = calibrate(document, Calibration::Numerals);
+        let words = calibrate(document, Calibration::NumeralsAndWords(&vocabulary));
+
+        assert_eq!(numerals.sum, 11 + 42);
+        assert_eq!(words.sum, 29 + 42);
+    }
+
+    #[test]
+    fn overlapping_words_at_the_end() {
+        assert_eq!(value("oneight"), 18);
+        assert_eq!(value("twone"), 21);
+        assert_eq!(value("threeight"), 38);
+        assert_eq!(value("fiveight"), 58);
+        assert_eq!(value("sevenine"), 79);
+        assert_eq!(value("eightwo"), 82);
+        assert_eq!(value("eighthree"), 83);
+        assert_eq!(value("nineight"), 98);
+    }
+
+    #[test]
+    fn overlapping_words_at_the_start() {
+        assert_eq!(value("oneightx5"), 15);
+        assert_eq!(value("x5twone"), 51);
+        assert_eq!(value("eightwo3"), 83);
+        assert_eq!(value("3nineight"), 38);
+    }
+
+    #[test]
+    fn single_digit_counts_twice() {
+        assert_eq!(value("abc7def"), 77);
+        assert_eq!(value("seven"), 77);
+    }
+
+    #[test]
+    fn german_vocabulary() {
+        assert_eq!(value_in("german", "xeinsiebenx"), 17);
+        assert_eq!(value_in("german", "fünfzweisechs"), 56);
+        assert_eq!(value_in("german", "two3eins"), 31);
+    }
+
+    #[test]
+    fn custom_words_and_zero() {
+        assert_eq!(value_in("english,zero=0", "zero5one"), 1);
+        assert_eq!(value_in("uno=1,dos=2", "dosuno"), 21);
+    }
+
+    #[test]
+    fn longer_word_wins_at_the_same_start() {
+        assert_eq!(value_in("ten=1,tense=5", "tense"), 55);
+    }
+
+    #[test]
+    fn invalid_vocabularies() {
+        assert!("klingon".parse::<Vocabulary>().is_err());
+        assert!("ten=10".parse::<Vocabulary>().is_err());
+        assert!("one=1,one=2".parse::<Vocabulary>().is_err());
+        assert!("=1".parse::<Vocabulary>().is_err());
+        assert!("".parse::<Vocabulary>().is_err());
+    }
+
+    #[test]
+    fn lines_without_digits_are_skipped_with_a_warning() {
+        let calibrated = calibrate("1abc2\nxyz\nseven\n7\n", Calibration::Numerals);
+
+        assert_eq!(calibrated.sum, 12 + 77);
+        assert_eq!(calibrated.warnings.len(), 2);
+        assert_eq!(
+            calibrated.warnings[0].kind,
+            ParseErrorKind::Expected("a digit")
+        );
+        assert_eq!(calibrated.warnings[0].line, 2);
+        assert_eq!(calibrated.warnings[1].line, 3);
+
+        // The parts only give the sum and leave warnings to the caller
+        let document = "1abc2\nxyz\n".to_string();
+        assert_eq!(Day1::part_one(&document).unwrap(), 12);
+        assert_eq!(Day1::part_two(&document).unwrap(), 12);
+    }
+
+    #[test]
+    fn explain_positions_and_sources() {
+        let vocabulary = Vocabulary::english();
+        let mode = Calibration::NumeralsAndWords(&vocabulary);
+        let explained = explain("éightwo3\nabc\n", mode);
+
+        let (first, last) = explained[0].digits.unwrap();
+        assert_eq!((first.value, first.source), (2, Source::Word));
+        assert_eq!(explained[0].column(&first), 5);
+        assert_eq!((last.value, last.source), (3, Source::Numeral));
+        assert_eq!(explained[0].column(&last), 8);
+
+        assert!(explained[1].digits.is_none());
+        assert_eq!(explained[1].line.number, 2);
+    }
+
+    #[test]
+    fn accented_lines() {
+        assert_eq!(numerals_value("café7naïve3"), Some(73));
+        assert_eq!(value("ünëeight5çà"), 85);
+        assert_eq!(value("éight5"), 55);
+        assert_eq!(value("señorsix"), 66);
+    }
+
+    #[test]
+    fn emoji_lines() {
+        assert_eq!(value("🎄one🎅two🎁"), 12);
+        assert_eq!(value("😀eight😀wo"), 88);
+        assert_eq!(numerals_value("🚀4👩‍🚀x9🛸"), Some(49));
+    }
+
+    #[test]
+    fn unicode_digits_are_reported() {
+        for (text, digit, column) in [("4٣", '٣', 2), ("🎄７one", '７', 2), ("nine५", '५', 5)]
+        {
+            let line = Line { number: 1, text };
+            let err = explain_line(&line, Calibration::Numerals)
+                .warning()
+                .unwrap();
+
+            assert_eq!(err.kind, ParseErrorKind::InvalidCharacter(digit));
+            assert_eq!(err.column, column);
+            assert_eq!(err.width, 1);
+        }
+    }
+
+    #[test]
+    fn unicode_digits_only_skip_their_line() {
+        let calibrated = calibrate("1٣\n12\nab\n", Calibration::Numerals);
+        let kinds: Vec<(usize, ParseErrorKind)> = calibrated
+            .warnings
+            .into_iter()
+            .map(|w| (w.line, w.kind))
+            .collect();
+
+        assert_eq!(calibrated.sum, 12);
+        assert_eq!(
+            kinds,
+            vec![
+                (1, ParseErrorKind::InvalidCharacter('٣')),
+                (3, ParseErrorKind::Expected("a digit")),
+            ]
+        );
+    }
+
+    #[test]
+    fn newer_scripts_are_reported() {
+        // Nag Mundari (Unicode 15), Kawi (15) and Garay (16)
+        for digit in ['\u{1E4F1}', '\u{11F53}', '\u{10D45}'] {
+            let text = format!("1{}", digit);
+            let line = Line {
+                number: 1,
+                text: &text,
+            };
+            let warning = explain_line(&line, Calibration::Numerals).warning();
+
+            assert_eq!(
+                warning.unwrap().kind,
+                ParseErrorKind::InvalidCharacter(digit)
+            );
+        }
+    }
+
+    /// Runs of exactly ten numeric characters that aren't decimal digits.
+    const NOT_DIGITS: &[u32] = &[0x17F0, 0x2080, 0x3220, 0x3280, 0xA6E6, 0x10F1D];
+
+    #[test]
+    fn decimal_zeros_keep_up_with_unicode() {
+        let numeric = |code: u32| char::from_u32(code).is_some_and(char::is_numeric);
+
+        assert!(DECIMAL_ZEROS.windows(2).all(|w| w[0] + 10 <= w[1]));
+        for &zero in DECIMAL_ZEROS {
+            assert!(
+                (zero..zero + 10).all(numeric),
+                "{:#X} is not ten digits",
+                zero
+            );
+        }
+
+        // A script gaining digits shows up as a new run of ten numeric
+        // characters, which has to be either listed or known not to be digits
+        let mut code = 0x80;
+        while code <= char::MAX as u32 {
+            let start = code;
+            while numeric(code) {
+                code += 1;
+            }
+
+            if code - start == 10 {
+                assert!(
+                    DECIMAL_ZEROS.contains(&start) || NOT_DIGITS.contains(&start),
+                    "unknown run of ten numeric characters at {:#X}, Unicode {:?}",
+                    start,
+                    char::UNICODE_VERSION
+                );
+            }
+
+            code += 1;
+        }
+    }
+
+    #[test]
+    fn other_numeric_characters_are_not_digits() {
+        assert_eq!(numerals_value("1²"), Some(11));
+        assert_eq!(numerals_value("½3Ⅻ"), Some(33));
+        assert_eq!(value("nine²"), 99);
+        assert!(calibrate("1²\n12\n", Calibration::Numerals)
+            .warnings
+            .is_empty());
+    }
 }
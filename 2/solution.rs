@@ -1,72 +1,564 @@
 // Advent of code: Day 2
 // Author: @alisinabh
 
-use std::collections::HashMap;
-use std::env;
-use std::fs::read_to_string;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1]).unwrap();
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+use crate::table;
 
-    let mut sum: i32 = 0;
+/// The bag part one asks about.
+const BAG: [(&str, i32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
 
-    for line in file.lines() {
-        if let Some(id) = game_id_if_possible(line) {
-            sum = sum + id;
+/// How many cubes of each colour a bag holds; colours it doesn't list have
+/// none.
+///
+/// Written as comma separated `colour=count` pairs, such as
+/// `red=12,green=13,blue=14,yellow=5`. Any colour name works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bag {
+    pub cubes: BTreeMap<String, i32>,
+}
+
+impl Bag {
+    /// Number of cubes of a colour, zero for colours not in the bag.
+    pub fn count(&self, colour: &str) -> i32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn empty() -> Self {
+        Bag {
+            cubes: BTreeMap::new(),
         }
     }
 
-    println!("The sum is {}", sum);
+    /// Number of cubes in the bag, which may not fit in an `i32`.
+    pub fn total(&self) -> i64 {
+        self.cubes.values().map(|&n| n as i64).sum()
+    }
+
+    /// Adds cubes until there are at least `cubes` of each colour.
+    fn widen(&mut self, cubes: &BTreeMap<String, i32>) {
+        for (colour, &count) in cubes {
+            let max = self.cubes.entry(colour.clone()).or_insert(count);
+            *max = (*max).max(count);
+        }
+    }
 
-    sum = 0;
+    /// Whether this bag has at least as many cubes of every colour as `other`.
+    pub fn holds(&self, other: &Bag) -> bool {
+        other
+            .cubes
+            .iter()
+            .all(|(colour, &count)| count <= self.count(colour))
+    }
 
-    for line in file.lines() {
-        sum = sum + calculate_game_power(line);
+    /// Several bags separated by `;`, e.g. `red=12,blue=14;red=20,blue=1`.
+    pub fn parse_list(s: &str) -> Result<Vec<Bag>, Box<dyn Error>> {
+        s.split(';').map(str::parse).collect()
     }
+}
 
-    println!("The power sum is {}", sum);
+impl Default for Bag {
+    fn default() -> Self {
+        Bag {
+            cubes: BAG.iter().map(|&(c, n)| (c.to_string(), n)).collect(),
+        }
+    }
 }
 
-fn calculate_game_power(line: &str) -> i32 {
-    let column_indx = line.find(':').unwrap();
+impl FromStr for Bag {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cubes = BTreeMap::new();
+
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((colour, count)) = entry.split_once('=') else {
+                return Err(format!("bag entry {} should be colour=count", entry).into());
+            };
 
-    let mut draws = (&line[column_indx + 1..]).split(';').map(|d| d.split(','));
+            let colour = colour.trim();
+            if colour.is_empty() || colour.contains(char::is_whitespace) {
+                return Err(format!("invalid colour name `{}`", colour).into());
+            }
 
-    let mut maximums: HashMap<&str, i32> = HashMap::with_capacity(3);
+            let count: i32 = count
+                .trim()
+                .parse()
+                .map_err(|e| format!("cube count for {}: {}", colour, e))?;
+            if count < 0 {
+                return Err(format!("cube count for {} is negative", colour).into());
+            }
 
-    while let Some(mut details) = draws.next() {
-        while let Some(item) = details.next() {
-            let [x, color] = item.trim().split(' ').collect::<Vec<_>>()[..] else {panic!("Invalid data")};
-            let x: i32 = x.parse::<i32>().unwrap();
+            if cubes.insert(colour.to_string(), count).is_some() {
+                return Err(format!("colour {} is listed twice", colour).into());
+            }
+        }
 
-            match maximums.get(color) {
-                Some(&max) if max > x => None,
-                _ => maximums.insert(color, x),
+        Ok(Bag { cubes })
+    }
+}
+
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .cubes
+            .iter()
+            .map(|(colour, count)| format!("{}={}", colour, count))
+            .collect();
+
+        write!(f, "{}", entries.join(","))
+    }
+}
+
+/// One handful of cubes shown from the bag, as the count of each colour.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Draw {
+    pub cubes: BTreeMap<String, i32>,
+}
+
+impl Draw {
+    fn parse(line: &Line, draw: &str) -> Result<Draw, ParseError> {
+        let mut cubes = BTreeMap::new();
+
+        for item in draw.split(',') {
+            let [count, colour] = item.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(line.error(ParseErrorKind::Expected("`<count> <colour>`"), item));
             };
+
+            let n: i32 = line.number(count)?;
+            if n < 0 {
+                return Err(line.error(
+                    ParseErrorKind::Expected("a cube count of zero or more"),
+                    count,
+                ));
+            }
+
+            if cubes.insert(colour.to_string(), n).is_some() {
+                return Err(line.error(ParseErrorKind::Expected("each colour once per draw"), item));
+            }
         }
+
+        Ok(Draw { cubes })
     }
 
-    maximums.values().fold(1, |acc, x| acc * x)
+    /// Number of cubes of a colour, zero for colours not in the draw.
+    pub fn count(&self, colour: &str) -> i32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> i64 {
+        self.cubes.values().map(|&n| n as i64).sum()
+    }
+
+    /// Log of the chance that one handful of this size pulled from `bag`,
+    /// without putting cubes back, has exactly these colours. Minus infinity
+    /// when the draw doesn't fit in the bag.
+    fn ln_likelihood(&self, bag: &Bag) -> f64 {
+        let ways: f64 = self
+            .cubes
+            .iter()
+            .map(|(colour, &count)| ln_choose(bag.count(colour) as i64, count as i64))
+            .sum();
+
+        ways - ln_choose(bag.total(), self.total())
+    }
+
+    /// Whether every cube of this draw could have come out of `bag`.
+    pub fn fits_in(&self, bag: &Bag) -> bool {
+        self.cubes
+            .iter()
+            .all(|(colour, &count)| count <= bag.count(colour))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub id: i32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    fn parse(line: &Line) -> Result<Game, ParseError> {
+        let (game, draws) = line
+            .text
+            .split_once(':')
+            .ok_or_else(|| line.error_at_end(ParseErrorKind::Expected("`:` after the game id")))?;
+
+        let id = game
+            .strip_prefix("Game ")
+            .ok_or_else(|| line.error(ParseErrorKind::Expected("`Game <id>`"), game))?;
+
+        let draws = draws
+            .split(';')
+            .map(|draw| Draw::parse(line, draw))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Game {
+            id: line.number(id)?,
+            draws,
+        })
+    }
+
+    /// Fewest cubes of each colour the bag must have held for this game,
+    /// i.e. the largest count of every colour over all draws.
+    pub fn minimum_bag(&self) -> Bag {
+        let mut bag = Bag::empty();
+
+        for draw in &self.draws {
+            bag.widen(&draw.cubes);
+        }
+
+        bag
+    }
+
+    /// Product of the minimum bag's counts.
+    pub fn power(&self) -> i32 {
+        self.minimum_bag().cubes.values().product()
+    }
+
+    pub fn is_possible(&self, bag: &Bag) -> bool {
+        bag.holds(&self.minimum_bag())
+    }
+
+    /// Index of the first draw that could not have come out of `bag`.
+    pub fn ruled_out_by(&self, bag: &Bag) -> Option<usize> {
+        if self.is_possible(bag) {
+            return None;
+        }
+
+        self.draws.iter().position(|draw| !draw.fits_in(bag))
+    }
 }
 
-fn game_id_if_possible(line: &str) -> Option<i32> {
-    let column_indx = line.find(':')?;
-    let game_id: i32 = (&line[5..column_indx]).parse().ok()?;
+pub struct Day2;
 
-    let mut draws = (&line[column_indx + 1..]).split(';').map(|d| d.split(','));
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+    type Output = i32;
 
-    while let Some(mut details) = draws.next() {
-        while let Some(item) = details.next() {
-            match item.trim().split(' ').collect::<Vec<_>>()[..] {
-                [x, "red"] if x.parse::<i32>().unwrap() > 12 => return None,
-                [x, "green"] if x.parse::<i32>().unwrap() > 13 => return None,
-                [x, "blue"] if x.parse::<i32>().unwrap() > 14 => return None,
-                [_, _] => (),
-                _ => panic!("unmatch {}", item),
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        error::lines(input).map(|l| Game::parse(&l)).collect()
+    }
+
+    fn part_one(games: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        Ok(possible_id_sum(games, &Bag::default()))
+    }
+
+    fn part_two(games: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        Ok(games.iter().map(Game::power).sum())
+    }
+}
+
+/// Runs a part like `Day2` does, but checking games against the given bag.
+pub fn solve(part: u8, document: &str, bag: &Bag) -> Result<String, Box<dyn Error>> {
+    let games = Day2::parse(document)?;
+
+    let answer = match part {
+        1 => possible_id_sum(&games, bag),
+        2 => Day2::part_two(&games)?,
+        p => return Err(format!("invalid part {}", p).into()),
+    };
+
+    Ok(answer.to_string())
+}
+
+/// Sum of the ids of the games that could have been played with `bag`.
+pub fn possible_id_sum(games: &[Game], bag: &Bag) -> i32 {
+    games
+        .iter()
+        .filter(|game| game.is_possible(bag))
+        .map(|game| game.id)
+        .sum()
+}
+
+/// Which games a bag allows, and for the rest the draw that shows it can't
+/// have been that bag.
+#[derive(Debug, Clone)]
+pub struct Feasibility<'a> {
+    pub bag: &'a Bag,
+    pub possible: Vec<i32>,
+    /// Impossible games along with the index of their first offending draw.
+    pub ruled_out: Vec<(&'a Game, usize)>,
+}
+
+impl<'a> Feasibility<'a> {
+    pub fn check(games: &'a [Game], bag: &'a Bag) -> Self {
+        let mut possible = Vec::new();
+        let mut ruled_out = Vec::new();
+
+        for game in games {
+            match game.ruled_out_by(bag) {
+                Some(draw) => ruled_out.push((game, draw)),
+                None => possible.push(game.id),
             }
         }
+
+        Feasibility {
+            bag,
+            possible,
+            ruled_out,
+        }
+    }
+
+    pub fn id_sum(&self) -> i32 {
+        self.possible.iter().sum()
+    }
+}
+
+impl fmt::Display for Feasibility<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let possible: Vec<String> = self.possible.iter().map(i32::to_string).collect();
+
+        writeln!(
+            f,
+            "bag {}: {} possible, id sum {}",
+            self.bag,
+            self.possible.len(),
+            self.id_sum()
+        )?;
+        match possible.is_empty() {
+            true => writeln!(f, "possible: none")?,
+            false => writeln!(f, "possible: {}", possible.join(", "))?,
+        }
+
+        if self.ruled_out.is_empty() {
+            return Ok(());
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .ruled_out
+            .iter()
+            .map(|&(game, index)| {
+                let draw = &game.draws[index];
+                let over: Vec<String> = draw
+                    .cubes
+                    .iter()
+                    .filter(|(colour, &count)| count > self.bag.count(colour))
+                    .map(|(colour, count)| {
+                        format!("{} {} > {}", count, colour, self.bag.count(colour))
+                    })
+                    .collect();
+
+                vec![
+                    game.id.to_string(),
+                    (index + 1).to_string(),
+                    over.join(", "),
+                ]
+            })
+            .collect();
+
+        write!(f, "{}", table::render(&["game", "draw", "too many"], &rows))
+    }
+}
+
+/// Fewest cubes of each colour that one bag needs to allow every game.
+pub fn minimum_bag(games: &[Game]) -> Bag {
+    let mut bag = Bag::empty();
+
+    for game in games {
+        bag.widen(&game.minimum_bag().cubes);
+    }
+
+    bag
+}
+
+/// Natural log of `n` choose `k`. There is no way to pick `k` out of `n`
+/// when `k` is negative or more than `n`, which gives minus infinity.
+fn ln_choose(n: i64, k: i64) -> f64 {
+    if !(0..=n).contains(&k) {
+        return f64::NEG_INFINITY;
+    }
+
+    // Picking `k` is picking the `n - k` left behind, so use the fewer
+    let k = k.min(n - k);
+
+    (1..=k).map(|i| ((n - k + i) as f64 / i as f64).ln()).sum()
+}
+
+/// What the games tell about the bag they were played with: the smallest
+/// bag allowing all of them, and how likely each candidate bag makes the
+/// draws that were seen.
+#[derive(Debug, Clone)]
+pub struct Inference<'a> {
+    pub minimum: Bag,
+    /// Log-likelihood of every draw of every game per candidate, `None`
+    /// when the candidate can't have produced some draw.
+    pub scores: Vec<(&'a Bag, Option<f64>)>,
+}
+
+impl<'a> Inference<'a> {
+    pub fn infer(games: &[Game], candidates: &'a [Bag]) -> Self {
+        let minimum = minimum_bag(games);
+
+        let scores = candidates
+            .iter()
+            .map(|bag| {
+                let score = bag.holds(&minimum).then(|| {
+                    games
+                        .iter()
+                        .flat_map(|game| &game.draws)
+                        .map(|draw| draw.ln_likelihood(bag))
+                        .sum()
+                });
+
+                (bag, score)
+            })
+            .collect();
+
+        Inference { minimum, scores }
+    }
+}
+
+impl fmt::Display for Inference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "minimum bag: {}", self.minimum)?;
+
+        let rows: Vec<Vec<String>> = self
+            .scores
+            .iter()
+            .map(|(bag, score)| {
+                let score = match score {
+                    Some(score) => format!("{:.3}", score),
+                    None => "impossible".to_string(),
+                };
+
+                vec![bag.to_string(), score]
+            })
+            .collect();
+
+        write!(f, "{}", table::render(&["bag", "log-likelihood"], &rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("test-input.txt");
+
+    fn bag(s: &str) -> Bag {
+        s.parse().unwrap()
+    }
+
+    fn bag_error(s: &str) -> String {
+        s.parse::<Bag>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn bags_parse_and_print() {
+        let parsed = bag(" red=12, blue=14,yellow=0 ");
+
+        assert_eq!(parsed.count("red"), 12);
+        assert_eq!(parsed.count("green"), 0);
+        assert_eq!(parsed.total(), 26);
+        assert_eq!(parsed.to_string(), "blue=14,red=12,yellow=0");
+        assert_eq!(bag("red=12,green=13,blue=14"), Bag::default());
+
+        let list = Bag::parse_list("red=1;blue=2,red=3").unwrap();
+        assert_eq!(list, vec![bag("red=1"), bag("red=3,blue=2")]);
+    }
+
+    #[test]
+    fn invalid_bags() {
+        assert_eq!(bag_error("red"), "bag entry red should be colour=count");
+        assert_eq!(bag_error("=3"), "invalid colour name ``");
+        assert_eq!(bag_error("dark red=3"), "invalid colour name `dark red`");
+        assert_eq!(bag_error("red=-1"), "cube count for red is negative");
+        assert_eq!(bag_error("red=1,red=2"), "colour red is listed twice");
+        assert!(bag_error("red=x").starts_with("cube count for red:"));
+        assert!(Bag::parse_list("red=1;blue").is_err());
+    }
+
+    #[test]
+    fn minimum_bags() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+
+        assert_eq!(games[0].minimum_bag(), bag("red=4,green=2,blue=6"));
+        assert_eq!(games[0].power(), 48);
+        assert_eq!(minimum_bag(&games), bag("red=20,green=13,blue=15"));
+    }
+
+    #[test]
+    fn feasibility_names_the_offending_draw() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        let default = Bag::default();
+        let report = Feasibility::check(&games, &default);
+
+        let ruled_out: Vec<(i32, usize)> = report
+            .ruled_out
+            .iter()
+            .map(|&(game, draw)| (game.id, draw))
+            .collect();
+
+        assert_eq!(report.possible, vec![1, 2, 5]);
+        assert_eq!(report.id_sum(), 8);
+        // Game 3 has 20 red in its first draw, game 4 15 blue in its last
+        assert_eq!(ruled_out, vec![(3, 0), (4, 2)]);
+
+        let roomy = bag("red=20,green=13,blue=15");
+        assert_eq!(Feasibility::check(&games, &roomy).id_sum(), 15);
+    }
+
+    #[test]
+    fn inference_scores_draws() {
+        let games = Day2::parse("Game 1: 1 red\n").unwrap();
+        let candidates = [bag("red=1,blue=1"), bag("red=1"), bag("blue=2")];
+        let inference = Inference::infer(&games, &candidates);
+
+        let scores: Vec<Option<f64>> = inference.scores.iter().map(|&(_, s)| s).collect();
+
+        assert_eq!(inference.minimum, bag("red=1"));
+        // One of the two cubes is red
+        assert!((scores[0].unwrap() - 0.5f64.ln()).abs() < 1e-12);
+        // The only cube is red
+        assert_eq!(scores[1], Some(0.0));
+        assert_eq!(scores[2], None);
     }
 
-    Some(game_id)
+    #[test]
+    fn inference_over_several_draws() {
+        // 2 red and 1 blue from red=2,blue=2 is C(2,2)·C(2,1)/C(4,3) = 1/2,
+        // then 1 blue from the full bag again is 2/4
+        let games = Day2::parse("Game 1: 2 red, 1 blue; 1 blue\n").unwrap();
+        let candidates = [bag("red=2,blue=2")];
+        let score = Inference::infer(&games, &candidates).scores[0].1.unwrap();
+
+        assert!((score - (0.5f64 * 0.5).ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ln_choose_out_of_range() {
+        assert_eq!(ln_choose(1, 2), f64::NEG_INFINITY);
+        assert_eq!(ln_choose(3, -1), f64::NEG_INFINITY);
+        assert_eq!(ln_choose(4, 0), 0.0);
+        assert!((ln_choose(4, 2) - 6f64.ln()).abs() < 1e-12);
+        assert!((ln_choose(4, 3) - 4f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn huge_bags_do_not_overflow() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        let candidates = [bag("red=2000000000,green=2000000000,blue=2000000000")];
+
+        assert_eq!(candidates[0].total(), 6_000_000_000);
+
+        let score = Inference::infer(&games, &candidates).scores[0].1.unwrap();
+        assert!(score.is_finite() && score < 0.0, "{}", score);
+    }
+
+    #[test]
+    fn negative_counts_are_rejected() {
+        let error = Day2::parse("Game 1: -3 red, 2 blue; 1 green\n").unwrap_err();
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::Expected("a cube count of zero or more")
+        );
+        assert_eq!((error.line, error.column, error.width), (1, 9, 2));
+    }
 }
@@ -1,125 +1,1200 @@
 // Advent of code: Day 3
 // Author: @alisinabh
 
-use std::env;
-use std::fs::read_to_string;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
-#[derive(Debug)]
-struct Engine {
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::json::Json;
+use crate::solution::Solution;
+
+#[derive(Debug, PartialEq)]
+pub struct Engine {
     part_numbers: Vec<PartNumber>,
-    symbols_matrix: Vec<(usize, usize)>,
-    gears: Vec<(usize, usize)>,
+    symbols_matrix: Vec<Symbol>,
+    grid: Grid,
 }
 
-#[derive(Debug)]
-struct PartNumber {
-    value: i64,
-    position: (usize, usize, usize),
+/// Dense index of the schematic, answering what sits at a cell in constant
+/// time so adjacency checks don't have to scan every symbol or number.
+#[derive(Debug, Default, PartialEq)]
+struct Grid {
+    width: usize,
+    height: usize,
+    adjacency: Adjacency,
+    // Index into `part_numbers` of the number covering each cell
+    parts: Vec<u32>,
+    // Index into `symbols_matrix` of the symbol at each cell
+    symbols: Vec<u32>,
 }
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1]).unwrap();
+impl Grid {
+    const EMPTY: u32 = u32::MAX;
 
-    let engine = Engine::from_file(file);
+    fn new(
+        (width, height): (usize, usize),
+        part_numbers: &[PartNumber],
+        symbols: &[Symbol],
+    ) -> Grid {
+        let mut grid = Grid {
+            width,
+            height,
+            adjacency: Adjacency::default(),
+            parts: vec![Self::EMPTY; width * height],
+            symbols: vec![Self::EMPTY; width * height],
+        };
 
-    // println!("Engine {:?}", engine);
+        for (index, p) in part_numbers.iter().enumerate() {
+            let (i, j, count) = p.position;
+            grid.parts[j * width + i..j * width + i + count].fill(index as u32);
+        }
 
-    println!("The sum is {}", engine.sum_part_numbers());
+        for (index, s) in symbols.iter().enumerate() {
+            let (i, j) = s.position;
+            grid.symbols[j * width + i] = index as u32;
+        }
 
-    println!("The gear ratio sum is {}", engine.sum_gear_ratios());
+        grid
+    }
+
+    fn part_at(&self, (i, j): (usize, usize)) -> Option<usize> {
+        match self.parts[j * self.width + i] {
+            Self::EMPTY => None,
+            index => Some(index as usize),
+        }
+    }
+
+    fn symbol_at(&self, (i, j): (usize, usize)) -> Option<usize> {
+        match self.symbols[j * self.width + i] {
+            Self::EMPTY => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Cells touching a run of `count` cells starting at `(i, j)`, in the
+    /// neighbourhood of the grid's adjacency, each of them once.
+    fn around(
+        &self,
+        (i, j): (usize, usize),
+        count: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let reach = self
+            .adjacency
+            .neighbourhood
+            .reach()
+            .min(self.width.max(self.height)) as isize;
+        let diagonals = self.adjacency.neighbourhood != Neighbourhood::Four;
+        let (row, start) = (j as isize, i as isize);
+
+        // Without wrapping the search stops at the edges, and with it after a
+        // whole turn around, past which cells would only come up again
+        let (rows, columns) = match self.adjacency.wrap {
+            false => (
+                (row - reach).max(0)..(row + reach + 1).min(height),
+                (start - reach).max(0)..(start + count as isize + reach).min(width),
+            ),
+            true => (
+                row - reach..row - reach + (2 * reach + 1).min(height),
+                start - reach..start - reach + (count as isize + 2 * reach).min(width),
+            ),
+        };
+
+        // Which row a cell is on only shows once it is wrapped back in
+        rows.flat_map(move |y| columns.clone().map(move |x| (x, y)))
+            .filter_map(move |(x, y)| self.cell(x, y))
+            .filter(move |&(x, y)| match (y == j, (i..i + count).contains(&x)) {
+                (true, own) => !own,
+                (false, above_or_below) => diagonals || above_or_below,
+            })
+    }
+
+    /// The cell at a position that may lie outside the grid, brought back
+    /// in when wrapping around.
+    fn cell(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        if self.adjacency.wrap {
+            return Some((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize));
+        }
+
+        ((0..width).contains(&x) && (0..height).contains(&y)).then_some((x as usize, y as usize))
+    }
+}
+
+/// Which cells around a number or symbol touch it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Left, right, above and below.
+    Four,
+    /// Diagonals too, as in the puzzle.
+    Eight,
+    /// Everything within the given Chebyshev distance.
+    Chebyshev(usize),
+}
+
+impl Neighbourhood {
+    fn reach(&self) -> usize {
+        match self {
+            Neighbourhood::Four | Neighbourhood::Eight => 1,
+            Neighbourhood::Chebyshev(radius) => *radius,
+        }
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "4" => Ok(Neighbourhood::Four),
+            "8" => Ok(Neighbourhood::Eight),
+            s => match s.strip_prefix("chebyshev=").map(str::parse) {
+                Some(Ok(0)) => Err("chebyshev radius has to be at least 1".into()),
+                Some(Ok(radius)) => Ok(Neighbourhood::Chebyshev(radius)),
+                _ => Err(format!(
+                    "unknown neighbourhood {}, expected 4, 8 or chebyshev=<radius>",
+                    s
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+/// How adjacency is decided: the neighbourhood, and whether it wraps around
+/// the edges of the schematic as on a torus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adjacency {
+    pub neighbourhood: Neighbourhood,
+    pub wrap: bool,
+}
+
+impl Default for Adjacency {
+    fn default() -> Self {
+        Adjacency {
+            neighbourhood: Neighbourhood::Eight,
+            wrap: false,
+        }
+    }
+}
+
+/// A number in the schematic; `position` is its column, row and length.
+#[derive(Debug, PartialEq)]
+pub struct PartNumber {
+    pub value: i64,
+    pub position: (usize, usize, usize),
+}
+
+/// Any character other than a digit or `.`; `position` is column and row.
+#[derive(Debug, PartialEq)]
+pub struct Symbol {
+    pub char: char,
+    pub position: (usize, usize),
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Engine;
+    type Output = i64;
+
+    fn parse(input: &str) -> Result<Engine, ParseError> {
+        Engine::from_file(input.to_string())
+    }
+
+    fn part_one(engine: &Engine) -> Result<i64, Box<dyn Error>> {
+        Ok(engine.sum_part_numbers())
+    }
+
+    fn part_two(engine: &Engine) -> Result<i64, Box<dyn Error>> {
+        Ok(engine.sum_gear_ratios(&GearRule::default()))
+    }
 }
 
 impl Engine {
-    fn from_file(file: String) -> Engine {
+    fn from_file(file: String) -> Result<Engine, ParseError> {
         let mut part_numbers: Vec<PartNumber> = Vec::new();
-        let mut symbols_matrix: Vec<(usize, usize)> = Vec::new();
-        let mut gears: Vec<(usize, usize)> = Vec::new();
+        let mut symbols_matrix: Vec<Symbol> = Vec::new();
 
-        let mut chars = file.chars();
+        let mut width = 0;
+        let mut height = 0;
 
-        let mut i = 0;
-        let mut j = 0;
+        // `lines` drops the `\r` of CRLF line ends, so columns come out the
+        // same for LF and CRLF files
+        for line in error::lines(&file) {
+            let j = line.number - 1;
 
-        let mut part_temp = String::new();
+            // Column and byte offset of the first digit of the current number
+            let mut number = None;
 
-        loop {
-            let char = chars.next();
+            for (i, (offset, char)) in line.text.char_indices().enumerate() {
+                if char.is_ascii_digit() {
+                    number.get_or_insert((i, offset));
+                    continue;
+                }
 
-            if char.is_some() && char.unwrap().is_digit(10) {
-                part_temp.push(char.unwrap());
-            } else {
-                if part_temp.len() > 0 {
-                    let count = part_temp.chars().count();
-                    part_numbers.push(PartNumber {
-                        value: part_temp.parse().unwrap(),
-                        position: (i - count, j, count),
-                    });
+                if let Some(start) = number.take() {
+                    part_numbers.push(Self::part_number(&line, start, offset)?);
                 }
-                part_temp.truncate(0);
-
-                match char {
-                    Some('.') => {}
-                    Some('\n') => {
-                        j = j + 1;
-                        i = 0;
-                        continue;
-                    }
-                    Some('\r') => continue,
-                    Some(sym) => {
-                        if sym == '*' {
-                            gears.push((i, j));
-                        }
-                        symbols_matrix.push((i, j))
-                    }
-                    None => break,
+
+                if char != '.' {
+                    symbols_matrix.push(Symbol {
+                        char,
+                        position: (i, j),
+                    });
                 }
             }
-            i = i + 1;
+
+            if let Some(start) = number {
+                part_numbers.push(Self::part_number(&line, start, line.text.len())?);
+            }
+
+            width = width.max(line.text.chars().count());
+            height = line.number;
+        }
+
+        let mut engine = Engine {
+            part_numbers,
+            symbols_matrix,
+            grid: Grid::default(),
+        };
+
+        engine.grid = Grid::new(
+            (width, height),
+            &engine.part_numbers,
+            &engine.symbols_matrix,
+        );
+
+        Ok(engine)
+    }
+
+    fn part_number(
+        line: &Line,
+        (i, offset): (usize, usize),
+        end: usize,
+    ) -> Result<PartNumber, ParseError> {
+        let digits = &line.text[offset..end];
+
+        Ok(PartNumber {
+            value: line.number(digits)?,
+            position: (i, line.number - 1, digits.len()),
+        })
+    }
+
+    /// Uses another neighbourhood, or wrapping, to decide what touches what.
+    pub fn with_adjacency(mut self, adjacency: Adjacency) -> Self {
+        self.grid.adjacency = adjacency;
+        self
+    }
+
+    pub fn part_numbers(&self) -> &[PartNumber] {
+        &self.part_numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols_matrix
+    }
+
+    /// Which symbols every part number touches, and the other way around.
+    pub fn graph(&self) -> Graph<'_> {
+        let mut part_symbols = vec![Vec::new(); self.part_numbers.len()];
+        let mut symbol_parts = vec![Vec::new(); self.symbols_matrix.len()];
+
+        for (part, p) in self.part_numbers.iter().enumerate() {
+            let (i, j, count) = p.position;
+
+            let mut touching: Vec<usize> = self
+                .grid
+                .around((i, j), count)
+                .filter_map(|cell| self.grid.symbol_at(cell))
+                .collect();
+
+            // Wrapping around can bring symbols up out of order
+            touching.sort();
+
+            for &symbol in &touching {
+                symbol_parts[symbol].push(part);
+            }
+            part_symbols[part] = touching;
         }
 
-        Engine {
-            part_numbers: part_numbers,
-            symbols_matrix: symbols_matrix,
-            gears: gears,
+        Graph {
+            engine: self,
+            part_symbols,
+            symbol_parts,
         }
     }
 
+    /// Whether a part number touches a symbol and so counts for part one.
+    fn is_counted(&self, p: &PartNumber) -> bool {
+        let (i, j, count) = p.position;
+
+        self.grid
+            .around((i, j), count)
+            .any(|cell| self.grid.symbol_at(cell).is_some())
+    }
+
     fn sum_part_numbers(&self) -> i64 {
         let mut sum = 0;
 
         for p in &self.part_numbers {
-            let (oi, oj, count) = p.position;
-            match self
-                .symbols_matrix
-                .iter()
-                .find(|&&(i, j)| j <= oj + 1 && j + 1 >= oj && i + 1 >= oi && oi + count >= i)
-            {
-                Some(_) => sum = sum + p.value,
-                None => {}
+            if self.is_counted(p) {
+                sum += p.value
             }
         }
 
         sum
     }
 
-    fn sum_gear_ratios(&self) -> i64 {
-        self.gears.iter().fold(0, |acc, &(gi, gj)| {
-            let gear_values: Vec<_> = self
-                .part_numbers
+    /// The schematic cell by cell, along with how each cell is drawn.
+    fn paint(&self, gears: &[Gear]) -> Vec<(char, Paint)> {
+        let width = self.grid.width;
+        let mut cells = vec![('.', Paint::Empty); width * self.grid.height];
+
+        for p in &self.part_numbers {
+            let (i, j, count) = p.position;
+            let paint = match self.is_counted(p) {
+                true => Paint::Counted,
+                false => Paint::Uncounted,
+            };
+
+            // Pad back any leading zeros the value lost
+            let digits = format!("{:0count$}", p.value, count = count);
+
+            for (k, digit) in digits.chars().enumerate() {
+                cells[j * width + i + k] = (digit, paint);
+            }
+        }
+
+        for s in &self.symbols_matrix {
+            let (i, j) = s.position;
+            cells[j * width + i] = (s.char, Paint::Symbol);
+        }
+
+        for gear in gears {
+            let (i, j) = self.symbols_matrix[gear.symbol].position;
+            cells[j * width + i].1 = Paint::Gear;
+        }
+
+        cells
+    }
+
+    /// Runs of equally painted cells in every row, as `(column, text, paint)`.
+    fn runs(&self, cells: &[(char, Paint)]) -> Vec<Vec<(usize, String, Paint)>> {
+        cells
+            .chunks(self.grid.width.max(1))
+            .take(self.grid.height)
+            .map(|row| {
+                let mut runs: Vec<(usize, String, Paint)> = Vec::new();
+
+                for (i, &(c, paint)) in row.iter().enumerate() {
+                    match runs.last_mut() {
+                        Some((_, text, last)) if *last == paint => text.push(c),
+                        _ => runs.push((i, c.to_string(), paint)),
+                    }
+                }
+
+                runs
+            })
+            .collect()
+    }
+
+    /// The schematic for a terminal: counted part numbers in green, the
+    /// others in red, gears in yellow with their ratio at the end of the row
+    /// and every other symbol in cyan. Without `colour` it is plain text,
+    /// for output that isn't a terminal.
+    pub fn render_terminal(&self, rule: &GearRule, colour: bool) -> String {
+        let gears = self.gears(rule);
+        let cells = self.paint(&gears);
+        let mut out = String::new();
+
+        let paint = |out: &mut String, text: &str, paint: Paint| match colour {
+            true => out.push_str(&format!("{}{}{}", paint.ansi(), text, RESET)),
+            false => out.push_str(text),
+        };
+
+        for (j, runs) in self.runs(&cells).into_iter().enumerate() {
+            for (_, text, p) in runs {
+                paint(&mut out, &text, p);
+            }
+
+            let ratios: Vec<String> = gears
                 .iter()
-                .filter(|p| {
-                    let (i, j, count) = p.position;
-                    j <= gj + 1 && j + 1 >= gj && gi + 1 >= i && gi <= i + count
+                .map(|gear| (&self.symbols_matrix[gear.symbol], gear.ratio))
+                .filter(|(symbol, _)| symbol.position.1 == j)
+                .map(|(symbol, ratio)| {
+                    format!("{}@{} = {}", symbol.char, symbol.position.0 + 1, ratio)
                 })
                 .collect();
 
-            // println!("gears {:?}", gear_values);
+            if !ratios.is_empty() {
+                out.push_str("  ");
+                paint(&mut out, &ratios.join(", "), Paint::Gear);
+            }
+
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// The schematic as an SVG image, coloured like [`Engine::render_terminal`]
+    /// with every gear's ratio written above it. Empty cells are left out to
+    /// keep large schematics small.
+    pub fn render_svg(&self, rule: &GearRule) -> String {
+        const CELL_WIDTH: usize = 8;
+        const CELL_HEIGHT: usize = 14;
+
+        let gears = self.gears(rule);
+        let cells = self.paint(&gears);
+
+        let mut out = format!(
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" ",
+                "font-family=\"monospace\" font-size=\"13\">\n",
+                "  <style>.counted {{ fill: #2e9e44 }} .uncounted {{ fill: #d13b3b }} ",
+                ".gear {{ fill: #c98a00; font-weight: bold }} .symbol {{ fill: #1f8fb3 }} ",
+                ".ratio {{ fill: #c98a00; font-size: 8px }}</style>\n",
+                "  <rect width=\"100%\" height=\"100%\" fill=\"#fdfdfd\"/>\n",
+            ),
+            (self.grid.width + 2) * CELL_WIDTH,
+            (self.grid.height + 2) * CELL_HEIGHT,
+        );
 
-            match gear_values[..] {
-                [x, y] => x.value * y.value + acc,
-                _ => acc,
+        for (j, runs) in self.runs(&cells).into_iter().enumerate() {
+            let y = (j + 2) * CELL_HEIGHT;
+
+            for (i, text, paint) in runs {
+                if let Some(class) = paint.class() {
+                    out.push_str(&format!(
+                        "  <text x=\"{}\" y=\"{}\" class=\"{}\" textLength=\"{}\">{}</text>\n",
+                        (i + 1) * CELL_WIDTH,
+                        y,
+                        class,
+                        text.chars().count() * CELL_WIDTH,
+                        xml_escape(&text)
+                    ));
+                }
             }
+        }
+
+        for gear in &gears {
+            let (i, j) = self.symbols_matrix[gear.symbol].position;
+
+            out.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" class=\"ratio\"><title>ratio {}</title>{}</text>\n",
+                (i + 1) * CELL_WIDTH,
+                (j + 1) * CELL_HEIGHT + CELL_HEIGHT / 2,
+                gear.ratio,
+                gear.ratio
+            ));
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// Symbols the rule accepts as gears, with the numbers around them.
+    pub fn gears(&self, rule: &GearRule) -> Vec<Gear> {
+        let mut gears = Vec::new();
+
+        for (index, symbol) in self.symbols_matrix.iter().enumerate() {
+            if !rule.symbols.contains(&symbol.char) {
+                continue;
+            }
+
+            let mut parts: Vec<usize> = self
+                .grid
+                .around(symbol.position, 1)
+                .filter_map(|cell| self.grid.part_at(cell))
+                .collect();
+
+            // A number touches the symbol once per adjacent digit
+            parts.sort();
+            parts.dedup();
+
+            if rule.neighbours.contains(&parts.len()) {
+                let ratio = rule
+                    .combine
+                    .apply(parts.iter().map(|&p| self.part_numbers[p].value));
+
+                gears.push(Gear {
+                    symbol: index,
+                    parts,
+                    ratio,
+                });
+            }
+        }
+
+        gears
+    }
+
+    pub fn sum_gear_ratios(&self, rule: &GearRule) -> i64 {
+        self.gears(rule).iter().map(|gear| gear.ratio).sum()
+    }
+}
+
+/// A symbol accepted by a [`GearRule`], by its index in `Engine::symbols`,
+/// along with the indices of the part numbers around it.
+#[derive(Debug, Clone)]
+pub struct Gear {
+    pub symbol: usize,
+    pub parts: Vec<usize>,
+    pub ratio: i64,
+}
+
+/// How the numbers around a gear make up its ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combine {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Combine::Product => "product",
+            Combine::Sum => "sum",
+            Combine::Max => "max",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "product" => Some(Combine::Product),
+            "sum" => Some(Combine::Sum),
+            "max" => Some(Combine::Max),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, values: impl Iterator<Item = i64>) -> i64 {
+        match self {
+            Combine::Product => values.product(),
+            Combine::Sum => values.sum(),
+            Combine::Max => values.max().unwrap_or(0),
+        }
+    }
+}
+
+/// Which symbols are gears and how their ratio is worked out.
+///
+/// Written `<symbols>:<neighbours>:<combine>`, where `neighbours` is how many
+/// numbers have to touch the symbol, either exact (`2`), a range (`2-4`) or
+/// open ended (`1-`). Part two is `*:2:product`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub neighbours: RangeInclusive<usize>,
+    pub combine: Combine,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule {
+            symbols: vec!['*'],
+            neighbours: 2..=2,
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl FromStr for GearRule {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split from the right so `:` itself can be one of the symbols
+        let [combine, neighbours, symbols] = s.rsplitn(3, ':').collect::<Vec<_>>()[..] else {
+            return Err(
+                format!("gear rule {} should be <symbols>:<neighbours>:<combine>", s).into(),
+            );
+        };
+
+        let symbols: Vec<char> = symbols.chars().collect();
+        if symbols.is_empty() {
+            return Err("gear rule has no symbols".into());
+        }
+
+        if let Some(&c) = symbols.iter().find(|c| c.is_ascii_digit() || **c == '.') {
+            return Err(format!("{:?} is not a symbol", c).into());
+        }
+
+        let neighbours = match neighbours.split_once('-') {
+            None => {
+                let n = neighbours.parse()?;
+                n..=n
+            }
+            Some((min, "")) => min.parse()?..=usize::MAX,
+            Some((min, max)) => min.parse()?..=max.parse()?,
+        };
+
+        if neighbours.is_empty() {
+            return Err(format!("empty neighbour range in gear rule {}", s).into());
+        }
+
+        let combine = Combine::from_name(combine)
+            .ok_or_else(|| format!("unknown combine {}, expected product, sum or max", combine))?;
+
+        Ok(GearRule {
+            symbols,
+            neighbours,
+            combine,
         })
     }
 }
+
+impl fmt::Display for GearRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols: String = self.symbols.iter().collect();
+
+        let neighbours = match (*self.neighbours.start(), *self.neighbours.end()) {
+            (min, max) if min == max => min.to_string(),
+            (min, usize::MAX) => format!("{}-", min),
+            (min, max) => format!("{}-{}", min, max),
+        };
+
+        write!(f, "{}:{}:{}", symbols, neighbours, self.combine.name())
+    }
+}
+
+/// Problems in a schematic that parsing lets through: rows of another length
+/// than the first, characters other than digits, `.` and ASCII punctuation,
+/// and numbers that stick out past the end of the first row.
+pub fn validate(schematic: &str) -> Vec<ParseError> {
+    let lines: Vec<Line> = error::lines(schematic).collect();
+    let width = lines.first().map_or(0, |l| l.text.chars().count());
+
+    let mut issues = Vec::new();
+
+    for line in &lines {
+        let length = line.text.chars().count();
+
+        if length != width {
+            issues.push(line.error_at(
+                ParseErrorKind::Expected("every row to be as long as the first"),
+                length.min(width) + 1,
+                length.abs_diff(width),
+            ));
+        }
+
+        for (offset, c) in line.text.char_indices() {
+            if !c.is_ascii_digit() && !c.is_ascii_punctuation() {
+                let at = &line.text[offset..offset + c.len_utf8()];
+                issues.push(line.error(ParseErrorKind::InvalidCharacter(c), at));
+            }
+        }
+
+        // Column of the first digit of the current number; the `.` added at
+        // the end closes a number the row ends with
+        let mut number = None;
+
+        for (i, c) in line.text.chars().chain(['.']).enumerate() {
+            if c.is_ascii_digit() {
+                number.get_or_insert(i);
+                continue;
+            }
+
+            match number.take() {
+                Some(start) if i > width => issues.push(line.error_at(
+                    ParseErrorKind::Expected("numbers to end within the first row's width"),
+                    start + 1,
+                    i - start,
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    issues
+}
+
+/// Runs a part like `Day3` does, but finding gears with the given rule and
+/// deciding what touches what with the given adjacency.
+pub fn solve(
+    part: u8,
+    schematic: &str,
+    rule: &GearRule,
+    adjacency: Adjacency,
+) -> Result<String, Box<dyn Error>> {
+    let engine = Day3::parse(schematic)?.with_adjacency(adjacency);
+
+    let answer = match part {
+        1 => Day3::part_one(&engine)?,
+        2 => engine.sum_gear_ratios(rule),
+        p => return Err(format!("invalid part {}", p).into()),
+    };
+
+    Ok(answer.to_string())
+}
+
+/// Adjacency between part numbers and symbols, by their index in
+/// `Engine::part_numbers` and `Engine::symbols`.
+#[derive(Debug)]
+pub struct Graph<'a> {
+    engine: &'a Engine,
+    /// Symbols touching each part number.
+    pub part_symbols: Vec<Vec<usize>>,
+    /// Part numbers touching each symbol.
+    pub symbol_parts: Vec<Vec<usize>>,
+}
+
+impl Graph<'_> {
+    /// Numbers and symbols with what they touch. Rows and columns are 1-based
+    /// like in error messages.
+    pub fn to_json(&self) -> Json {
+        let parts = &self.engine.part_numbers;
+        let symbols = &self.engine.symbols_matrix;
+
+        let part = |p: &PartNumber| {
+            let (i, j, count) = p.position;
+            vec![
+                ("value".into(), Json::Number(p.value as f64)),
+                ("row".into(), (j + 1).into()),
+                ("column".into(), (i + 1).into()),
+                ("length".into(), count.into()),
+            ]
+        };
+
+        let symbol = |s: &Symbol| {
+            let (i, j) = s.position;
+            vec![
+                ("char".into(), s.char.to_string().into()),
+                ("row".into(), (j + 1).into()),
+                ("column".into(), (i + 1).into()),
+            ]
+        };
+
+        let part_entries = parts
+            .iter()
+            .zip(&self.part_symbols)
+            .map(|(p, touching)| {
+                let mut entry = part(p);
+                let touching = touching.iter().map(|&s| Json::Object(symbol(&symbols[s])));
+                entry.push(("symbols".into(), Json::Array(touching.collect())));
+                Json::Object(entry)
+            })
+            .collect();
+
+        let symbol_entries = symbols
+            .iter()
+            .zip(&self.symbol_parts)
+            .map(|(s, touching)| {
+                let mut entry = symbol(s);
+                let touching = touching.iter().map(|&p| Json::Object(part(&parts[p])));
+                entry.push(("parts".into(), Json::Array(touching.collect())));
+                Json::Object(entry)
+            })
+            .collect();
+
+        Json::Object(vec![
+            ("parts".into(), Json::Array(part_entries)),
+            ("symbols".into(), Json::Array(symbol_entries)),
+        ])
+    }
+
+    /// Graphviz graph with a box per part number and a circle per symbol.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph schematic {\n");
+
+        for (index, p) in self.engine.part_numbers.iter().enumerate() {
+            let (i, j, _) = p.position;
+            out.push_str(&format!(
+                "    p{} [shape=box, label=\"{}\", tooltip=\"row {} column {}\"];\n",
+                index,
+                p.value,
+                j + 1,
+                i + 1
+            ));
+        }
+
+        for (index, s) in self.engine.symbols_matrix.iter().enumerate() {
+            let (i, j) = s.position;
+            let label = match s.char {
+                '"' | '\\' => format!("\\{}", s.char),
+                c => c.to_string(),
+            };
+
+            out.push_str(&format!(
+                "    s{} [shape=circle, label=\"{}\", tooltip=\"row {} column {}\"];\n",
+                index,
+                label,
+                j + 1,
+                i + 1
+            ));
+        }
+
+        for (part, touching) in self.part_symbols.iter().enumerate() {
+            for symbol in touching {
+                out.push_str(&format!("    p{} -- s{};\n", part, symbol));
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// How a cell of the schematic is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Paint {
+    Empty,
+    Counted,
+    Uncounted,
+    Gear,
+    Symbol,
+}
+
+impl Paint {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Paint::Empty => "\x1b[2m",
+            Paint::Counted => "\x1b[32m",
+            Paint::Uncounted => "\x1b[31m",
+            Paint::Gear => "\x1b[1;33m",
+            Paint::Symbol => "\x1b[36m",
+        }
+    }
+
+    /// Class of the cell in SVG output, `None` for cells left out.
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            Paint::Empty => None,
+            Paint::Counted => Some("counted"),
+            Paint::Uncounted => Some("uncounted"),
+            Paint::Gear => Some("gear"),
+            Paint::Symbol => Some("symbol"),
+        }
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// How `--render` draws the schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Terminal,
+    Svg,
+}
+
+impl FromStr for RenderFormat {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "terminal" => Ok(RenderFormat::Terminal),
+            "svg" => Ok(RenderFormat::Svg),
+            f => Err(format!("unknown render format {}, expected terminal or svg", f).into()),
+        }
+    }
+}
+
+/// How `--graph` writes out a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Json,
+    Dot,
+}
+
+impl FromStr for GraphFormat {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(GraphFormat::Json),
+            "dot" => Ok(GraphFormat::Dot),
+            f => Err(format!("unknown graph format {}, expected json or dot", f).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("test-input.txt");
+
+    fn engine(schematic: &str) -> Engine {
+        Day3::parse(schematic).unwrap()
+    }
+
+    /// Kind, row and column of every issue `validate` finds.
+    fn issues(schematic: &str) -> Vec<(ParseErrorKind, usize, usize)> {
+        validate(schematic)
+            .into_iter()
+            .map(|e| (e.kind, e.line, e.column))
+            .collect()
+    }
+
+    fn answers(schematic: &str, neighbourhood: &str, wrap: bool) -> (i64, i64) {
+        let adjacency = Adjacency {
+            neighbourhood: neighbourhood.parse().unwrap(),
+            wrap,
+        };
+        let engine = engine(schematic).with_adjacency(adjacency);
+
+        (
+            Day3::part_one(&engine).unwrap(),
+            Day3::part_two(&engine).unwrap(),
+        )
+    }
+
+    #[test]
+    fn four_neighbourhood_skips_diagonals() {
+        // 467, 35, 633, 592 and 664 only touch their symbols diagonally
+        assert_eq!(answers(EXAMPLE, "4", false), (2547, 0));
+    }
+
+    #[test]
+    fn chebyshev_reaches_further() {
+        // 114 is two columns from the `*` below it, 58 two rows from the `#`
+        assert_eq!(answers(EXAMPLE, "chebyshev=2", false), (4361 + 114 + 58, 0));
+    }
+
+    #[test]
+    fn huge_radius_is_clamped_to_the_grid() {
+        // Every number of the example, each of them once
+        let all = 467 + 114 + 35 + 633 + 617 + 58 + 592 + 755 + 664 + 598;
+
+        assert_eq!(answers(EXAMPLE, "chebyshev=20000", false).0, all);
+        assert_eq!(answers(EXAMPLE, "chebyshev=20000", true).0, all);
+    }
+
+    #[test]
+    fn wrap_joins_opposite_edges() {
+        let schematic = "12..*\n.....\n..7..\n";
+
+        assert_eq!(answers(schematic, "8", false), (0, 0));
+        assert_eq!(answers(schematic, "8", true), (12, 0));
+        // The row below the last is the first again
+        assert_eq!(answers("..7\n...\n..#\n", "4", true).0, 7);
+    }
+
+    #[test]
+    fn wrap_keeps_every_four_way_neighbour() {
+        // Wrapping only ever adds neighbours to those the grid already has
+        for schematic in [
+            "2*3\n",
+            "12\n.*\n",
+            "12\n*.\n",
+            "1.\n*.\n",
+            ".1\n.*\n",
+            "123\n..#\n",
+        ] {
+            let (plain, wrapped) = (
+                answers(schematic, "4", false),
+                answers(schematic, "4", true),
+            );
+            assert_eq!(plain, wrapped, "{:?}", schematic);
+        }
+
+        assert_eq!(answers("2*3\n", "4", true), (5, 6));
+        // The left of 1 is the `*` at the end of the row, the right of 2 too
+        assert_eq!(answers("1.*\n", "4", true).0, 1);
+        assert_eq!(answers("#.2\n", "4", true).0, 2);
+        // Above the first row is the last, right below the number
+        assert_eq!(answers("12\n..\n*.\n", "4", true).0, 12);
+        assert_eq!(answers("12\n..\n..*\n", "4", true).0, 0);
+    }
+
+    #[test]
+    fn wrap_counts_a_gear_neighbour_once() {
+        // On a one row torus 2 and 3 touch the `*` from both sides
+        assert_eq!(answers("2*3\n", "8", true), (5, 6));
+    }
+
+    #[test]
+    fn graph_edges_of_the_example() {
+        let engine = engine(EXAMPLE);
+        let graph = engine.graph();
+        let value = |p: usize| engine.part_numbers()[p].value;
+
+        // 467 touches the `*` on row 2, column 4, which 35 touches too
+        assert_eq!(value(0), 467);
+        assert_eq!(graph.part_symbols[0], vec![0]);
+        assert_eq!(engine.symbols()[0].position, (3, 1));
+        assert_eq!(graph.symbol_parts[0], vec![0, 2]);
+        assert_eq!(value(2), 35);
+
+        // 114 and 58 touch nothing
+        assert_eq!((value(1), value(5)), (114, 58));
+        assert!(graph.part_symbols[1].is_empty());
+        assert!(graph.part_symbols[5].is_empty());
+
+        let edges: usize = graph.part_symbols.iter().map(Vec::len).sum();
+        assert_eq!(edges, 8);
+    }
+
+    #[test]
+    fn graph_json_uses_one_based_positions() {
+        let engine = engine(EXAMPLE);
+        let json = engine.graph().to_json();
+
+        let part = &json.get("parts").unwrap().as_array().unwrap()[0];
+        let symbol = &part.get("symbols").unwrap().as_array().unwrap()[0];
+
+        assert_eq!(part.get("value").unwrap().as_f64(), Some(467.0));
+        assert_eq!(symbol.get("char").unwrap().as_str(), Some("*"));
+        assert_eq!(symbol.get("row").unwrap().as_f64(), Some(2.0));
+        assert_eq!(symbol.get("column").unwrap().as_f64(), Some(4.0));
+
+        let lonely = &json.get("parts").unwrap().as_array().unwrap()[1];
+        assert_eq!(lonely.get("symbols").unwrap().as_array(), Some(&[][..]));
+    }
+
+    #[test]
+    fn dot_escapes_quotes_and_backslashes() {
+        let engine = engine("1\".\n\\..\n");
+        let dot = engine.graph().to_dot();
+
+        assert!(dot.contains(r#"s0 [shape=circle, label="\"", tooltip="row 1 column 2"];"#));
+        assert!(dot.contains(r#"s1 [shape=circle, label="\\", tooltip="row 2 column 1"];"#));
+        assert!(dot.contains("p0 -- s0;\n    p0 -- s1;\n"));
+    }
+
+    #[test]
+    fn gear_rules_round_trip() {
+        for text in ["*:2:product", ":#:1-:sum", "*:2-4:max"] {
+            let rule: GearRule = text.parse().unwrap();
+            assert_eq!(rule.to_string(), text);
+        }
+
+        let rule: GearRule = ":#:1-:sum".parse().unwrap();
+        assert_eq!(rule.symbols, vec![':', '#']);
+        assert_eq!(rule.neighbours, 1..=usize::MAX);
+        assert_eq!(rule.combine, Combine::Sum);
+
+        assert_eq!(
+            "*:2:product".parse::<GearRule>().unwrap(),
+            GearRule::default()
+        );
+        assert_eq!("*:2-4:max".parse::<GearRule>().unwrap().neighbours, 2..=4);
+    }
+
+    #[test]
+    fn invalid_gear_rules() {
+        for text in [
+            "*:3-1:sum",
+            "5:2:sum",
+            "*:x:product",
+            ".:2:sum",
+            ":2:sum",
+            "*:2:min",
+        ] {
+            assert!(text.parse::<GearRule>().is_err(), "{} parsed", text);
+        }
+    }
+
+    #[test]
+    fn gears_under_other_rules() {
+        let engine = engine(EXAMPLE);
+        let sum = |rule: &str| engine.sum_gear_ratios(&rule.parse().unwrap());
+
+        // 467+35, 617, 755+598 around the `*`s and 633 at the `#`
+        assert_eq!(sum("*#:1-:sum"), 3105);
+        assert_eq!(sum("*:2:product"), 467835);
+        assert_eq!(sum("*:1:max"), 617);
+        assert_eq!(sum("*:3-:product"), 0);
+
+        let gears = engine.gears(&"*:2:product".parse().unwrap());
+        let symbols: Vec<usize> = gears.iter().map(|g| g.symbol).collect();
+        assert_eq!(symbols, vec![0, 5]);
+        assert_eq!(gears[0].parts, vec![0, 2]);
+    }
+
+    #[test]
+    fn plain_rendering_has_no_escapes() {
+        let engine = engine(EXAMPLE);
+        let rule = GearRule::default();
+
+        let plain = engine.render_terminal(&rule, false);
+        let lines: Vec<&str> = plain.lines().collect();
+
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(lines[0], "467..114..");
+        assert_eq!(lines[1], "...*......  *@4 = 16345");
+        assert_eq!(lines.len(), 10);
+
+        let coloured = engine.render_terminal(&rule, true);
+        assert!(coloured.starts_with("\x1b[32m467\x1b[0m"));
+    }
+
+    #[test]
+    fn svg_rendering() {
+        let svg = engine(EXAMPLE).render_svg(&GearRule::default());
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"class="counted" textLength="24">467</text>"#));
+        assert!(svg.contains(r#"class="uncounted" textLength="24">114</text>"#));
+        assert!(svg.contains(r#"<text x="32" y="42" class="gear" textLength="8">*</text>"#));
+        assert!(svg.contains("<title>ratio 16345</title>16345</text>"));
+
+        let svg = engine("1<2\n&..\n").render_svg(&GearRule::default());
+        assert!(svg.contains(r#"class="symbol" textLength="8">&lt;</text>"#));
+        assert!(svg.contains(r#"class="symbol" textLength="8">&amp;</text>"#));
+        assert!(!svg.contains(">&<") && !svg.contains("><<"));
+    }
+
+    #[test]
+    fn crlf_parses_like_lf() {
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        let (lf, crlf) = (engine(EXAMPLE), engine(&crlf));
+
+        assert_eq!(lf, crlf);
+        assert_eq!(Day3::part_one(&crlf).unwrap(), 4361);
+        assert_eq!(Day3::part_two(&crlf).unwrap(), 467835);
+    }
+
+    #[test]
+    fn numbers_may_end_and_start_rows() {
+        assert!(validate("12\n34\n").is_empty());
+        assert!(validate(EXAMPLE).is_empty());
+    }
+
+    #[test]
+    fn validate_ragged_rows() {
+        let short = ParseErrorKind::Expected("every row to be as long as the first");
+
+        assert_eq!(issues("...\n.\n...\n"), vec![(short.clone(), 2, 2)]);
+        assert_eq!(issues("...\n.....\n"), vec![(short, 2, 4)]);
+    }
+
+    #[test]
+    fn validate_unexpected_characters() {
+        assert_eq!(
+            issues("..a\n.é.\n"),
+            vec![
+                (ParseErrorKind::InvalidCharacter('a'), 1, 3),
+                (ParseErrorKind::InvalidCharacter('é'), 2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_numbers_past_the_first_row() {
+        let ragged = ParseErrorKind::Expected("every row to be as long as the first");
+        let past = ParseErrorKind::Expected("numbers to end within the first row's width");
+
+        assert_eq!(issues("....\n..123\n"), vec![(ragged, 2, 5), (past, 2, 3)]);
+    }
+}
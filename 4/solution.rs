@@ -1,37 +1,40 @@
 // Advent of code: Day 4
 // Author: @alisinabh
 
-use std::env;
 use std::error::Error;
-use std::fs::read_to_string;
-use std::num::ParseIntError;
+
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
 
 #[derive(Debug)]
-struct ScratchCard {
-    card_number: usize,
+pub struct ScratchCard {
     winning_numbers: Vec<i32>,
     numbers: Vec<i32>,
     match_count: Option<usize>,
 }
 
 impl ScratchCard {
-    fn parse(line: &str) -> Result<ScratchCard, Box<dyn Error>> {
-        let [card, numbers] = line.split(':').collect::<Vec<_>>()[..] else { return Err("Bad input".into())};
-
-        let [_, number] = card.split_whitespace().collect::<Vec<_>>()[..] else {return Err("Bad input".into())};
+    fn parse(line: &Line) -> Result<ScratchCard, ParseError> {
+        let [_, numbers] = line.text.split(':').collect::<Vec<_>>()[..] else {
+            return Err(line.error(ParseErrorKind::Expected("`Card <n>: <numbers>`"), line.text));
+        };
 
-        let [winning_numbers, numbers] = numbers.split('|').collect::<Vec<_>>()[..] else {return Err("Bad input".into())};
+        let [winning_numbers, numbers] = numbers.split('|').collect::<Vec<_>>()[..] else {
+            return Err(line.error(
+                ParseErrorKind::Expected("a single `|` between the number lists"),
+                numbers,
+            ));
+        };
 
-        let mut winning_numbers = Self::parse_numbers(winning_numbers)?;
-        let mut numbers = Self::parse_numbers(numbers)?;
+        let mut winning_numbers = Self::parse_numbers(line, winning_numbers)?;
+        let mut numbers = Self::parse_numbers(line, numbers)?;
 
         winning_numbers.sort();
         numbers.sort();
 
         let mut card = ScratchCard {
-            card_number: number.parse()?,
-            winning_numbers: winning_numbers,
-            numbers: numbers,
+            winning_numbers,
+            numbers,
             match_count: None,
         };
 
@@ -74,30 +77,37 @@ impl ScratchCard {
         self.match_count = Some(matches)
     }
 
-    fn parse_numbers(numbers: &str) -> Result<Vec<i32>, ParseIntError> {
-        numbers.split_whitespace().map(|n| n.parse()).collect()
+    fn parse_numbers(line: &Line, numbers: &str) -> Result<Vec<i32>, ParseError> {
+        numbers.split_whitespace().map(|n| line.number(n)).collect()
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1])?;
+pub struct Day4;
 
-    let mut power = 0;
-    let mut cards: Vec<ScratchCard> = Vec::new();
+impl Solution for Day4 {
+    type Input = Vec<ScratchCard>;
+    type Output = usize;
 
-    for line in file.lines() {
-        let card = ScratchCard::parse(line)?;
-        power = power + card.calculate_power().ok_or("overflow")?;
-        cards.push(card);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        error::lines(input)
+            .map(|l| ScratchCard::parse(&l))
+            .collect()
     }
 
-    println!("pile power {}", power);
+    fn part_one(cards: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        let mut power = 0;
 
-    let prize_count =
-        (0..cards.len()).fold(0, |acc, i| acc + cards[i].prize_card_count(&cards[i + 1..]));
+        for card in cards {
+            power += card.calculate_power().ok_or("overflow")? as usize;
+        }
+
+        Ok(power)
+    }
 
-    println!("pile prize count: {}", prize_count);
+    fn part_two(cards: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        let prize_count =
+            (0..cards.len()).fold(0, |acc, i| acc + cards[i].prize_card_count(&cards[i + 1..]));
 
-    Ok(())
+        Ok(prize_count)
+    }
 }
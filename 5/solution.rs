@@ -2,13 +2,14 @@
 // Author: @alisinabh
 
 use std::collections::HashMap;
-use std::env;
 use std::error::Error;
-use std::fs::read_to_string;
 use std::ops::Range;
 
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+
 #[derive(Debug)]
-struct SeedData {
+pub struct SeedData {
     seeds: Vec<u64>,
     ranges: HashMap<(String, String), ConversionRange>,
 }
@@ -30,15 +31,25 @@ const PATH: [&str; 8] = [
 ];
 
 impl SeedData {
-    fn parse(data: String) -> Result<SeedData, Box<dyn Error>> {
-        let mut lines = data.lines();
-
-        let Some(seeds_line) = lines.next() else { return Err("missing seeds line".into()) };
-        let Some(seeds) = seeds_line.split(':').last() else { return Err("bad seeds line".into()) };
+    fn parse(data: String) -> Result<SeedData, ParseError> {
+        let mut lines = error::lines(&data);
+
+        let Some(seeds_line) = lines.next() else {
+            return Err(ParseError::at_end(
+                &data,
+                ParseErrorKind::UnexpectedEnd("the seeds line"),
+            ));
+        };
+        let Some(seeds) = seeds_line.text.strip_prefix("seeds:") else {
+            return Err(seeds_line.error(
+                ParseErrorKind::Expected("`seeds: <numbers>`"),
+                seeds_line.text,
+            ));
+        };
 
         let seeds = seeds
             .split_whitespace()
-            .map(|x| x.parse())
+            .map(|x| seeds_line.number(x))
             .collect::<Result<_, _>>()?;
 
         let mut ranges = HashMap::new();
@@ -46,18 +57,24 @@ impl SeedData {
         _ = lines.next();
 
         while let Some(map_line) = lines.next() {
-            let [src, "to", dst] = map_line
+            let name = map_line
+                .text
                 .split_whitespace()
                 .next()
-                .unwrap()
-                .split('-')
-                .collect::<Vec<_>>()[..] else {return Err("bad map line".into())};
+                .unwrap_or(map_line.text);
+
+            let [src, "to", dst] = name.split('-').collect::<Vec<_>>()[..] else {
+                return Err(map_line.error(
+                    ParseErrorKind::Expected("`<source>-to-<destination> map:`"),
+                    name,
+                ));
+            };
 
             let mut conversion_ranges = ConversionRange::new();
 
             loop {
                 match lines.next() {
-                    Some(s) if !s.trim().is_empty() => conversion_ranges.add_range(s)?,
+                    Some(l) if !l.text.trim().is_empty() => conversion_ranges.add_range(&l)?,
                     _ => break,
                 };
             }
@@ -65,10 +82,15 @@ impl SeedData {
             ranges.insert((src.to_string(), dst.to_string()), conversion_ranges);
         }
 
-        Ok(SeedData {
-            seeds: seeds,
-            ranges: ranges,
-        })
+        if PATH
+            .windows(2)
+            .any(|path| !ranges.contains_key(&(path[0].into(), path[1].into())))
+        {
+            let kind = ParseErrorKind::UnexpectedEnd("a map for every step from seed to location");
+            return Err(ParseError::at_end(&data, kind));
+        }
+
+        Ok(SeedData { seeds, ranges })
     }
 
     fn traverse(&self, seed: u64) -> u64 {
@@ -145,11 +167,18 @@ impl ConversionRange {
         ConversionRange { ranges: Vec::new() }
     }
 
-    fn add_range(&mut self, line: &str) -> Result<(), Box<dyn Error>> {
+    fn add_range(&mut self, line: &Line) -> Result<(), ParseError> {
         let [dst_range, src_range, len] = line
+            .text
             .split_whitespace()
-            .map(|x| x.parse())
-            .collect::<Result<Vec<u64>, _>>()?[..] else { return Err("invalid range".into()) };
+            .map(|x| line.number(x))
+            .collect::<Result<Vec<u64>, _>>()?[..]
+        else {
+            return Err(line.error(
+                ParseErrorKind::Expected("`<destination> <source> <length>`"),
+                line.text,
+            ));
+        };
 
         self.ranges.push((
             src_range..src_range + len,
@@ -174,29 +203,29 @@ impl ConversionRange {
 
     fn get_next_value(&self, value: u64) -> u64 {
         match self.get_related_range(value) {
-            Some(&(_, diff)) => (value as i64 + diff as i64) as u64,
+            Some(&(_, diff)) => (value as i64 + diff) as u64,
             None => value,
         }
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1])?;
+pub struct Day5;
 
-    let seed_data = SeedData::parse(file)?;
+impl Solution for Day5 {
+    type Input = SeedData;
+    type Output = u64;
 
-    println!(
-        "part one: {}",
-        seed_data
-            .find_lowest_location()
-            .ok_or("location not found")?
-    );
+    fn parse(input: &str) -> Result<SeedData, ParseError> {
+        SeedData::parse(input.to_string())
+    }
 
-    println!(
-        "part two: {}",
-        seed_data.find_lowest_location_using_ranges()
-    );
+    fn part_one(seed_data: &SeedData) -> Result<u64, Box<dyn Error>> {
+        Ok(seed_data
+            .find_lowest_location()
+            .ok_or("location not found")?)
+    }
 
-    Ok(())
+    fn part_two(seed_data: &SeedData) -> Result<u64, Box<dyn Error>> {
+        Ok(seed_data.find_lowest_location_using_ranges())
+    }
 }
@@ -1,40 +1,40 @@
 // Advent of code: Day 6
 // Author: @alisinabh
 
-use std::env;
 use std::error::Error;
-use std::fs::read_to_string;
+use std::num::ParseIntError;
+
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
 
 #[derive(Debug)]
-struct RaceHistory {
+pub struct RaceHistory {
     races: Vec<Race>,
 }
 
 impl RaceHistory {
-    fn parse(file: &str) -> Result<RaceHistory, Box<dyn Error>> {
-        let mut lines = file
-            .lines()
-            .map(|l| l.split_whitespace().collect::<Vec<_>>());
-
-        let binding = lines
-            .find(|x| x[0] == "Time:")
-            .ok_or("time entries not found")?;
-        let times_line = binding.iter();
-
-        let binding = lines
-            .find(|x| x[0] == "Distance:")
-            .ok_or("distance entries not found")?;
-        let distances_line = binding.iter();
+    fn parse(file: &str) -> Result<RaceHistory, ParseError> {
+        let times_line = find_line(file, "Time:")?;
+        let distances_line = find_line(file, "Distance:")?;
 
         let times: Vec<_> = times_line
+            .text
+            .split_whitespace()
             .skip(1)
-            .map(|&t| t.parse::<u64>())
+            .map(|t| times_line.number::<u64>(t))
             .collect::<Result<_, _>>()?;
         let distances: Vec<_> = distances_line
+            .text
+            .split_whitespace()
             .skip(1)
-            .map(|&d| d.parse::<u64>())
+            .map(|d| distances_line.number::<u64>(d))
             .collect::<Result<_, _>>()?;
 
+        if times.len() != distances.len() {
+            let kind = ParseErrorKind::Expected("as many distances as times");
+            return Err(distances_line.error(kind, distances_line.text));
+        }
+
         let races = times
             .iter()
             .zip(distances.iter())
@@ -45,8 +45,14 @@ impl RaceHistory {
     }
 }
 
+fn find_line<'a>(file: &'a str, label: &'static str) -> Result<Line<'a>, ParseError> {
+    error::lines(file)
+        .find(|l| l.text.split_whitespace().next() == Some(label))
+        .ok_or_else(|| ParseError::at_end(file, ParseErrorKind::UnexpectedEnd(label)))
+}
+
 #[derive(Debug)]
-struct Race {
+pub struct Race {
     time: u64,
     best_distance: u64,
 }
@@ -54,25 +60,25 @@ struct Race {
 impl Race {
     fn new(time: u64, best_distance: u64) -> Self {
         Self {
-            time: time,
-            best_distance: best_distance,
+            time,
+            best_distance,
         }
     }
 
-    fn parse_single(file: &str) -> Result<Self, Box<dyn Error>> {
-        let mut lines = file.lines().map(|l| l.split(':').collect::<Vec<_>>());
+    fn parse_single(file: &str) -> Result<Self, ParseError> {
+        let time = Self::parse_kerned(&find_line(file, "Time:")?)?;
+        let distance = Self::parse_kerned(&find_line(file, "Distance:")?)?;
 
-        let time = lines.find(|x| x[0] == "Time").ok_or("time not found")?[1]
-            .replace(" ", "")
-            .parse()?;
+        Ok(Race::new(time, distance))
+    }
 
-        let distance = lines
-            .find(|x| x[0] == "Distance")
-            .ok_or("distance not found")?[1]
-            .replace(" ", "")
-            .parse()?;
+    // Reads the numbers after the label as one number, ignoring the spaces between them
+    fn parse_kerned(line: &Line) -> Result<u64, ParseError> {
+        let (_, digits) = line.text.split_once(':').unwrap_or_default();
 
-        Ok(Race::new(time, distance))
+        digits.replace(' ', "").parse().map_err(|e: ParseIntError| {
+            line.error(ParseErrorKind::InvalidNumber(e.to_string()), digits.trim())
+        })
     }
 
     fn record_breaking_possibility_count(&self) -> u64 {
@@ -113,23 +119,26 @@ impl Race {
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let file = read_to_string(&args[1])?;
-
-    let race_history = RaceHistory::parse(&file)?;
-
-    let beating_scenarios = race_history
-        .races
-        .iter()
-        .map(|r| r.record_breaking_possibility_count())
-        .fold(1, |acc, c| c * acc);
+pub struct Day6;
 
-    println!("part one: {}", beating_scenarios);
+impl Solution for Day6 {
+    // Part two reads the same sheet as one race with the spaces removed
+    type Input = (RaceHistory, Race);
+    type Output = u64;
 
-    let race = Race::parse_single(&file)?;
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((RaceHistory::parse(input)?, Race::parse_single(input)?))
+    }
 
-    println!("part two: {}", race.record_breaking_possibility_count());
+    fn part_one((race_history, _): &Self::Input) -> Result<u64, Box<dyn Error>> {
+        Ok(race_history
+            .races
+            .iter()
+            .map(|r| r.record_breaking_possibility_count())
+            .product())
+    }
 
-    Ok(())
+    fn part_two((_, race): &Self::Input) -> Result<u64, Box<dyn Error>> {
+        Ok(race.record_breaking_possibility_count())
+    }
 }
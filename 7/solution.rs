@@ -2,10 +2,12 @@
 // Author: @alisinabh
 
 use std::collections::HashMap;
-use std::env;
 use std::error::Error;
 use std::fmt;
 
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+
 #[derive(Copy, Clone, Debug)]
 enum HandType {
     FiveOfAKind = 6,
@@ -18,7 +20,7 @@ enum HandType {
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
     cards: Vec<Card>,
     r#type: HandType,
     type_with_joker: HandType,
@@ -26,27 +28,40 @@ struct Hand {
 }
 
 impl Hand {
-    fn from_line(line: &str) -> Result<Self, Box<dyn Error>> {
-        let [hand, bid] = line.split_whitespace().collect::<Vec<_>>()[..] else { return Err("invalid hand line".into()) };
+    fn from_line(line: &Line) -> Result<Self, ParseError> {
+        let [hand, bid] = line.text.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(line.error(ParseErrorKind::Expected("`<cards> <bid>`"), line.text));
+        };
 
-        let bid: u64 = bid.parse()?;
+        let bid: u64 = line.number(bid)?;
         let cards: Vec<_> = hand
-            .chars()
-            .map(|c| Card::from_char(&c).ok_or("invalid char"))
+            .char_indices()
+            .map(|(i, c)| {
+                Card::from_char(&c).ok_or_else(|| {
+                    line.error(
+                        ParseErrorKind::InvalidCharacter(c),
+                        &hand[i..i + c.len_utf8()],
+                    )
+                })
+            })
             .collect::<Result<_, _>>()?;
 
+        if cards.len() != 5 {
+            return Err(line.error(ParseErrorKind::Expected("five cards"), hand));
+        }
+
         let hand_type = Self::calculate_type(&cards);
         let hand_type_with_joker = Self::maybe_calculate_type_with_joker(&cards);
 
         Ok(Hand {
-            cards: cards,
-            bid: bid,
+            cards,
+            bid,
             r#type: hand_type,
             type_with_joker: hand_type_with_joker,
         })
     }
 
-    fn calculate_type(cards: &Vec<Card>) -> HandType {
+    fn calculate_type(cards: &[Card]) -> HandType {
         let mut map: HashMap<u8, u8> = HashMap::new();
 
         for c in cards {
@@ -76,7 +91,7 @@ impl Hand {
         }
     }
 
-    fn maybe_calculate_type_with_joker(cards: &Vec<Card>) -> HandType {
+    fn maybe_calculate_type_with_joker(cards: &[Card]) -> HandType {
         let joker_count = cards.iter().filter(|c| c.is_joker()).count() as u8;
 
         if joker_count > 0 {
@@ -86,7 +101,7 @@ impl Hand {
         }
     }
 
-    fn calculate_type_with_joker(cards: &Vec<Card>, joker_count: u8) -> HandType {
+    fn calculate_type_with_joker(cards: &[Card], joker_count: u8) -> HandType {
         if joker_count == 5 {
             return HandType::FiveOfAKind;
         }
@@ -154,12 +169,13 @@ impl Card {
     fn from_char(c: &char) -> Option<Self> {
         if *c == 'J' {
             Some(Card::Joker)
-        } else if let Some(indx) = CARD_ORDER.iter().position(|&r| r == *c) {
-            Some(Card::Normal {
-                power: (indx + 1) as u8,
-            })
         } else {
-            None
+            CARD_ORDER
+                .iter()
+                .position(|&r| r == *c)
+                .map(|indx| Card::Normal {
+                    power: (indx + 1) as u8,
+                })
         }
     }
 
@@ -190,42 +206,36 @@ impl fmt::Debug for Card {
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let data = std::fs::read_to_string(&args[1])?;
+fn total_winnings(hands: &[&Hand]) -> u64 {
+    hands
+        .iter()
+        .zip(1..)
+        .fold(0, |sum, (h, rank)| sum + rank * h.bid)
+}
+
+pub struct Day7;
 
-    let mut hands = Vec::new();
+impl Solution for Day7 {
+    type Input = Vec<Hand>;
+    type Output = u64;
 
-    for line in data.lines() {
-        hands.push(Hand::from_line(line)?);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        error::lines(input).map(|l| Hand::from_line(&l)).collect()
     }
 
-    hands.sort_by(|a, b| a.magic_number().cmp(&b.magic_number()));
+    fn part_one(hands: &Self::Input) -> Result<u64, Box<dyn Error>> {
+        let mut hands: Vec<_> = hands.iter().collect();
 
-    let mut rank = 1;
-    let mut sum = 0;
+        hands.sort_by_key(|h| h.magic_number());
 
-    for h in &hands {
-        sum += rank * h.bid;
-        rank += 1;
+        Ok(total_winnings(&hands))
     }
 
-    println!("part one: {}", sum);
-
-    hands.sort_by(|a, b| {
-        a.magic_number_with_joker()
-            .cmp(&b.magic_number_with_joker())
-    });
+    fn part_two(hands: &Self::Input) -> Result<u64, Box<dyn Error>> {
+        let mut hands: Vec<_> = hands.iter().collect();
 
-    let mut rank = 1;
-    let mut sum = 0;
+        hands.sort_by_key(|h| h.magic_number_with_joker());
 
-    for h in hands {
-        sum += rank * h.bid;
-        rank += 1;
+        Ok(total_winnings(&hands))
     }
-
-    println!("part two: {}", sum);
-
-    Ok(())
 }
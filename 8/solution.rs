@@ -1,13 +1,15 @@
-// Advent of code: Day 7
+// Advent of code: Day 8
 // Author: @alisinabh
 
 use std::collections::HashMap;
-use std::env;
 use std::error::Error;
 
+use crate::error::{self, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+
 fn next_pos<'a>(
     instructions: &'a [Direction],
-    map: &'a HashMap<String, (String, String)>,
+    map: &'a Network,
     pos: &'a str,
     step: usize,
 ) -> Result<&'a str, Box<dyn Error>> {
@@ -19,32 +21,33 @@ fn next_pos<'a>(
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let data = std::fs::read_to_string(&args[2])?;
+pub struct Day8;
 
-    let part_one = args[1] == "1";
-    let part_two = args[1] == "2";
+impl Solution for Day8 {
+    type Input = (Vec<Direction>, Network);
+    type Output = usize;
 
-    let (instructions, map) = parse(&data)?;
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
 
-    if part_one {
+    fn part_one((instructions, map): &Self::Input) -> Result<usize, Box<dyn Error>> {
         let mut pos: String = "AAA".into();
         let mut step = 0;
 
         while pos != "ZZZ" {
-            pos = next_pos(&instructions, &map, &pos, step)?.into();
+            pos = next_pos(instructions, map, &pos, step)?.into();
             step += 1;
         }
 
-        println!("part one took {} steps", step);
+        Ok(step)
     }
 
-    if part_two {
+    fn part_two((instructions, map): &Self::Input) -> Result<usize, Box<dyn Error>> {
         let mut pos: Vec<String> = Vec::new();
 
         for m in map.keys() {
-            if m.ends_with("A") {
+            if m.ends_with('A') {
                 pos.push(m.to_string());
             }
         }
@@ -55,32 +58,30 @@ fn main() -> Result<(), Box<dyn Error>> {
                 let mut p: &str = x;
                 let mut i: usize = 0;
                 loop {
-                    p = &next_pos(&instructions, &map, &p, i).unwrap();
+                    p = next_pos(instructions, map, p, i)?;
                     i += 1;
-                    if p.ends_with("Z") {
+                    if p.ends_with('Z') {
                         break;
                     }
                 }
-                i
+                Ok(i)
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
 
         let gcd = z_min_steps
             .iter()
             .cloned()
             .reduce(|acc, x| gcd(x, acc))
-            .unwrap();
+            .ok_or("no starting nodes")?;
 
         let steps: usize = z_min_steps
             .iter()
             .cloned()
             .reduce(|acc, x| x * acc / gcd)
-            .unwrap();
+            .ok_or("no starting nodes")?;
 
-        println!("part twp steps {}", steps);
+        Ok(steps)
     }
-
-    Ok(())
 }
 
 fn gcd(x: usize, y: usize) -> usize {
@@ -97,50 +98,68 @@ fn gcd(x: usize, y: usize) -> usize {
 }
 
 #[derive(Debug)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
 impl Direction {
-    fn from_char(c: &char) -> Result<Self, Box<dyn Error>> {
+    fn from_char(c: &char) -> Option<Self> {
         match *c {
-            'L' => Ok(Direction::Left),
-            'R' => Ok(Direction::Right),
-            _ => Err("invalid instruction".into()),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
         }
     }
 }
 
-fn parse(
-    data: &str,
-) -> Result<(Vec<Direction>, HashMap<String, (String, String)>), Box<dyn Error>> {
-    let mut lines = data.lines();
+pub type Network = HashMap<String, (String, String)>;
+
+fn parse(data: &str) -> Result<(Vec<Direction>, Network), ParseError> {
+    let mut lines = error::lines(data);
 
-    let instructions_line = lines.next().ok_or("instruction not found")?;
+    let instructions_line = lines.next().ok_or_else(|| {
+        ParseError::at_end(data, ParseErrorKind::UnexpectedEnd("the instructions"))
+    })?;
 
     let instructions: Vec<Direction> = instructions_line
-        .chars()
-        .map(|c| Direction::from_char(&c))
+        .text
+        .char_indices()
+        .map(|(i, c)| {
+            Direction::from_char(&c).ok_or_else(|| {
+                let at = &instructions_line.text[i..i + c.len_utf8()];
+                instructions_line.error(ParseErrorKind::InvalidCharacter(c), at)
+            })
+        })
         .collect::<Result<_, _>>()?;
 
+    if instructions.is_empty() {
+        let kind = ParseErrorKind::Expected("at least one instruction");
+        return Err(instructions_line.error_at_end(kind));
+    }
+
     _ = lines.next();
 
     let mut map: HashMap<_, _> = HashMap::new();
 
-    while let Some(l) = lines.next() {
-        let [pos, next_pos] = l.split('=').collect::<Vec<_>>()[..] else {return Err("invalid pos".into())};
+    for l in lines {
+        let [pos, targets] = l.text.split('=').collect::<Vec<_>>()[..] else {
+            return Err(l.error(
+                ParseErrorKind::Expected("`<node> = (<left>, <right>)`"),
+                l.text,
+            ));
+        };
 
         let pos = pos.trim();
-        let mut next_pos = next_pos
-            .split(',')
-            .map(|c| c.replace("(", "").replace(")", "").replace(" ", ""));
+        let mut next_pos = targets.split(',').map(|c| c.replace(['(', ')', ' '], ""));
+
+        let missing = || l.error(ParseErrorKind::Expected("`(<left>, <right>)`"), targets);
 
         map.insert(
             pos.into(),
             (
-                next_pos.next().ok_or("next pos not foun")?,
-                next_pos.next().ok_or("next pos not found")?,
+                next_pos.next().ok_or_else(missing)?,
+                next_pos.next().ok_or_else(missing)?,
             ),
         );
     }
@@ -1,21 +1,28 @@
 // Advent of code: Day 9
 // Author: @alisinabh
 
-use std::env;
 use std::error::Error;
 
+use crate::error::{self, Line, ParseError, ParseErrorKind};
+use crate::solution::Solution;
+
 #[derive(Debug)]
-struct Serie {
+pub struct Serie {
     readings: Vec<i64>,
 }
 
 impl Serie {
-    fn parse(line: &str) -> Result<Self, Box<dyn Error>> {
-        let readings = line
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let readings: Vec<i64> = line
+            .text
             .split_whitespace()
-            .map(|r| r.parse::<i64>())
+            .map(|r| line.number(r))
             .collect::<Result<_, _>>()?;
 
+        if readings.is_empty() {
+            return Err(line.error_at_end(ParseErrorKind::Expected("at least one reading")));
+        }
+
         Ok(Self { readings })
     }
 
@@ -38,7 +45,7 @@ impl Serie {
         extrapolated.push(self.readings.clone());
 
         loop {
-            let items = Self::single_extrapolate(&extrapolated.last().unwrap());
+            let items = Self::single_extrapolate(extrapolated.last().unwrap());
 
             if items.iter().all(|&x| x == 0) {
                 break;
@@ -58,22 +65,21 @@ impl Serie {
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<_> = env::args().collect();
-    let data = std::fs::read_to_string(&args[1])?;
-
-    let series: Vec<Serie> = data
-        .lines()
-        .map(|l| Serie::parse(l))
-        .collect::<Result<_, _>>()?;
+pub struct Day9;
 
-    let sum = series.iter().fold(0, |acc, x| x.next_value() + acc);
+impl Solution for Day9 {
+    type Input = Vec<Serie>;
+    type Output = i64;
 
-    println!("part one {}", sum);
-
-    let sum = series.iter().fold(0, |acc, x| x.previous_value() + acc);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        error::lines(input).map(|l| Serie::parse(&l)).collect()
+    }
 
-    println!("part two {}", sum);
+    fn part_one(series: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        Ok(series.iter().map(|x| x.next_value()).sum())
+    }
 
-    Ok(())
+    fn part_two(series: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        Ok(series.iter().map(|x| x.previous_value()).sum())
+    }
 }
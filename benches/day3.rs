@@ -0,0 +1,126 @@
+// Advent of code 2023: day 3 scaling benchmark
+// Author: @alisinabh
+//
+// Times day 3 on generated schematics of growing size, up to 10,000×10,000
+// cells, to show that parsing and both parts stay linear in the size of the
+// schematic. Run with `cargo bench --bench day3 [-- <largest side>]`.
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use aoc::bench::{BenchReport, Stage};
+use aoc::table;
+
+const SIDES: [usize; 4] = [1_000, 2_500, 5_000, 10_000];
+const ITERATIONS: usize = 3;
+const SYMBOLS: &[u8] = b"*#+$/@=%-&";
+
+/// Small xorshift generator so the schematics are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// A square schematic with about the density of numbers and symbols of a
+/// real puzzle input.
+fn generate(side: usize, rng: &mut Rng) -> String {
+    let mut text = Vec::with_capacity((side + 1) * side);
+
+    for _ in 0..side {
+        let mut i = 0;
+
+        while i < side {
+            match rng.below(100) {
+                0..=5 if i + 4 <= side => {
+                    let digits = 1 + rng.below(3) as usize;
+                    text.push(b'1' + rng.below(9) as u8);
+                    text.extend((1..digits).map(|_| b'0' + rng.below(10) as u8));
+                    text.push(b'.');
+                    i += digits + 1;
+                }
+                6..=8 => {
+                    text.push(SYMBOLS[rng.below(SYMBOLS.len() as u64) as usize]);
+                    i += 1;
+                }
+                _ => {
+                    text.push(b'.');
+                    i += 1;
+                }
+            }
+        }
+
+        text.push(b'\n');
+    }
+
+    String::from_utf8(text).expect("schematics are ASCII")
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let largest = match std::env::args().skip(1).find(|a| !a.starts_with('-')) {
+        Some(side) => side.parse()?,
+        None => SIDES[SIDES.len() - 1],
+    };
+
+    let mut rng = Rng(0x2023_0003);
+    let mut results = Vec::new();
+    let mut rows = Vec::new();
+
+    for side in SIDES.into_iter().filter(|&side| side <= largest) {
+        let text = generate(side, &mut rng);
+        let name = format!("{}x{}", side, side);
+        let timings = aoc::bench(3, &name, &text, ITERATIONS)?;
+
+        let cells = (side * side) as f64;
+        let per_cell = |stage: Stage| {
+            let timing = timings.iter().find(|r| r.stage == stage);
+            timing.map_or(0.0, |r| r.median_ns as f64 / cells)
+        };
+
+        rows.push(vec![
+            name,
+            format!("{:.2}", per_cell(Stage::Parse)),
+            format!("{:.2}", per_cell(Stage::PartOne)),
+            format!("{:.2}", per_cell(Stage::PartTwo)),
+        ]);
+
+        results.extend(timings);
+    }
+
+    let report = BenchReport {
+        iterations: ITERATIONS,
+        results,
+    };
+
+    let header = [
+        "schematic",
+        "parse ns/cell",
+        "part 1 ns/cell",
+        "part 2 ns/cell",
+    ];
+
+    print!("{}", report.table());
+    println!();
+    print!("{}", table::render(&header, &rows));
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
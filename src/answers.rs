@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error;
+use crate::solve;
+use crate::table;
+
+/// A known correct answer for one part of a day over one input file.
+#[derive(Debug, Clone)]
+pub struct ExpectedAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+/// Expected answers of a day, read from `N/answers.txt` where every line is
+/// `<input file> <part> <answer>` and lines starting with `#` are comments.
+#[derive(Debug)]
+pub struct Answers {
+    pub entries: Vec<ExpectedAnswer>,
+}
+
+impl Answers {
+    pub fn parse(day: u8, data: &str) -> Result<Self, Box<dyn Error>> {
+        let mut entries = Vec::new();
+
+        for (n, line) in data.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let [input, part, answer] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(format!(
+                    "day {} answers line {}: expected `<input> <part> <answer>`",
+                    day,
+                    n + 1
+                )
+                .into());
+            };
+
+            entries.push(ExpectedAnswer {
+                day,
+                part: part.parse()?,
+                input: input.to_string(),
+                answer: answer.to_string(),
+            });
+        }
+
+        Ok(Answers { entries })
+    }
+
+    pub fn load(root: &Path, day: u8) -> Result<Self, Box<dyn Error>> {
+        let path = root.join(day.to_string()).join("answers.txt");
+
+        match read_to_string(&path) {
+            Ok(data) => Self::parse(day, &data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Answers { entries: vec![] }),
+            Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+        }
+    }
+}
+
+/// Adds an answer to `N/answers.txt` unless that input and part is already
+/// registered. Returns whether the file changed.
+pub fn register(
+    root: &Path,
+    day: u8,
+    input: &str,
+    part: u8,
+    answer: &str,
+) -> Result<bool, Box<dyn Error>> {
+    let known = Answers::load(root, day)?;
+
+    if known
+        .entries
+        .iter()
+        .any(|e| e.input == input && e.part == part)
+    {
+        return Ok(false);
+    }
+
+    let path = root.join(day.to_string()).join("answers.txt");
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+    if known.entries.is_empty() && file.metadata()?.len() == 0 {
+        writeln!(file, "# input part answer")?;
+    }
+
+    writeln!(file, "{} {} {}", input, part, answer)?;
+
+    Ok(true)
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Mismatch(String),
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub expected: ExpectedAnswer,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn is_pass(&self) -> bool {
+        matches!(self.outcome, Outcome::Pass)
+    }
+}
+
+/// Runs every registered answer of the given days and reports the outcome of each.
+pub fn verify(root: &Path, days: &[u8]) -> Result<Vec<Check>, Box<dyn Error>> {
+    let mut checks = Vec::new();
+
+    for &day in days {
+        for expected in Answers::load(root, day)?.entries {
+            let path: PathBuf = root.join(day.to_string()).join(&expected.input);
+
+            let outcome = match read_to_string(&path) {
+                Ok(input) => match solve(day, expected.part, &input) {
+                    Ok(answer) if answer == expected.answer => Outcome::Pass,
+                    Ok(answer) => Outcome::Mismatch(answer),
+                    Err(e) => {
+                        Outcome::Failed(error::with_file(e, &path.to_string_lossy()).to_string())
+                    }
+                },
+                Err(e) => Outcome::Failed(format!("{}: {}", path.display(), e)),
+            };
+
+            checks.push(Check { expected, outcome });
+        }
+    }
+
+    Ok(checks)
+}
+
+/// Table of verification results, one row per check.
+pub struct Report<'a>(pub &'a [Check]);
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|c| {
+                let (actual, status) = match &c.outcome {
+                    Outcome::Pass => (c.expected.answer.clone(), "ok"),
+                    Outcome::Mismatch(actual) => (actual.clone(), "MISMATCH"),
+                    Outcome::Failed(e) => {
+                        (e.lines().next().unwrap_or_default().to_string(), "ERROR")
+                    }
+                };
+
+                vec![
+                    c.expected.day.to_string(),
+                    c.expected.part.to_string(),
+                    c.expected.input.clone(),
+                    c.expected.answer.clone(),
+                    actual,
+                    status.to_string(),
+                ]
+            })
+            .collect();
+
+        let header = ["day", "part", "input", "expected", "actual", "status"];
+
+        write!(f, "{}", table::render(&header, &rows))
+    }
+}
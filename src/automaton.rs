@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, VecDeque};
+
+const ROOT: usize = 0;
+
+/// Aho-Corasick automaton finding every occurrence of a set of patterns in
+/// one pass over the haystack, however many patterns there are.
+///
+/// Patterns are matched byte-wise and may overlap each other, so "eightwo"
+/// reports both "eight" and "two". Empty patterns never match.
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    states: Vec<State>,
+    lengths: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    next: BTreeMap<u8, usize>,
+    fail: usize,
+    // Patterns ending in this state, including those reached through `fail`
+    outputs: Vec<usize>,
+}
+
+/// One occurrence of a pattern, as a byte range of the haystack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Automaton {
+    /// Builds the automaton; pattern indices in matches follow the order given.
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut states = vec![State::default()];
+        let mut lengths = Vec::new();
+
+        for (pattern, bytes) in patterns.into_iter().enumerate() {
+            let bytes = bytes.as_ref();
+            let mut state = ROOT;
+
+            for &byte in bytes {
+                state = match states[state].next.get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        states.push(State::default());
+                        let next = states.len() - 1;
+                        states[state].next.insert(byte, next);
+                        next
+                    }
+                };
+            }
+
+            if !bytes.is_empty() {
+                states[state].outputs.push(pattern);
+            }
+            lengths.push(bytes.len());
+        }
+
+        // Breadth first, so the failure state of a node is always complete
+        // before its children look at it
+        let mut queue: VecDeque<usize> = states[ROOT].next.values().copied().collect();
+
+        while let Some(state) = queue.pop_front() {
+            let edges: Vec<(u8, usize)> =
+                states[state].next.iter().map(|(&b, &s)| (b, s)).collect();
+
+            for (byte, child) in edges {
+                let fail = Self::step(&states, states[state].fail, byte);
+                let inherited = states[fail].outputs.clone();
+
+                states[child].fail = fail;
+                states[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Automaton { states, lengths }
+    }
+
+    /// Every match in the haystack, ordered by where it ends.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a str) -> Matches<'a> {
+        Matches {
+            automaton: self,
+            haystack: haystack.as_bytes(),
+            position: 0,
+            state: ROOT,
+            output: 0,
+        }
+    }
+
+    fn step(states: &[State], mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = states[state].next.get(&byte) {
+                return next;
+            }
+
+            if state == ROOT {
+                return ROOT;
+            }
+
+            state = states[state].fail;
+        }
+    }
+}
+
+/// Iterator over the matches of an [`Automaton`] in a haystack.
+pub struct Matches<'a> {
+    automaton: &'a Automaton,
+    haystack: &'a [u8],
+    position: usize,
+    state: usize,
+    output: usize,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        loop {
+            let outputs = &self.automaton.states[self.state].outputs;
+
+            if let Some(&pattern) = outputs.get(self.output) {
+                self.output += 1;
+
+                return Some(Match {
+                    pattern,
+                    start: self.position - self.automaton.lengths[pattern],
+                    end: self.position,
+                });
+            }
+
+            let &byte = self.haystack.get(self.position)?;
+
+            self.state = Automaton::step(&self.automaton.states, self.state, byte);
+            self.position += 1;
+            self.output = 0;
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::error::Error;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::json::Json;
+use crate::solution::Solution;
+use crate::table;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::PartOne => "part_one",
+            Stage::PartTwo => "part_two",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Stage::Parse),
+            "part_one" => Some(Stage::PartOne),
+            "part_two" => Some(Stage::PartTwo),
+            _ => None,
+        }
+    }
+}
+
+/// Summary of the samples taken for one stage of a day.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub day: u8,
+    pub input: String,
+    pub stage: Stage,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl BenchResult {
+    fn from_samples(day: u8, input: &str, stage: Stage, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+
+        BenchResult {
+            day,
+            input: input.to_string(),
+            stage,
+            min_ns: samples[0].as_nanos() as u64,
+            median_ns: samples[samples.len() / 2].as_nanos() as u64,
+            max_ns: samples[samples.len() - 1].as_nanos() as u64,
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("day".into(), self.day.into()),
+            ("input".into(), self.input.as_str().into()),
+            ("stage".into(), self.stage.name().into()),
+            ("min_ns".into(), self.min_ns.into()),
+            ("median_ns".into(), self.median_ns.into()),
+            ("max_ns".into(), self.max_ns.into()),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Option<Self> {
+        Some(BenchResult {
+            day: json.get("day")?.as_f64()? as u8,
+            input: json.get("input")?.as_str()?.to_string(),
+            stage: Stage::from_name(json.get("stage")?.as_str()?)?,
+            min_ns: json.get("min_ns")?.as_f64()? as u64,
+            median_ns: json.get("median_ns")?.as_f64()? as u64,
+            max_ns: json.get("max_ns")?.as_f64()? as u64,
+        })
+    }
+}
+
+/// Times parse and the requested parts of a solution separately, running
+/// each stage `iterations` times.
+pub fn measure<S: Solution>(
+    day: u8,
+    input_name: &str,
+    input: &str,
+    parts: &[u8],
+    iterations: usize,
+) -> Result<Vec<BenchResult>, Box<dyn Error>> {
+    let iterations = iterations.max(1);
+    let mut results = Vec::new();
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        black_box(S::parse(black_box(input))?);
+        samples.push(start.elapsed());
+    }
+    results.push(BenchResult::from_samples(
+        day,
+        input_name,
+        Stage::Parse,
+        samples,
+    ));
+
+    let parsed = S::parse(input)?;
+
+    for &part in parts {
+        let (stage, run): (_, fn(&S::Input) -> _) = match part {
+            1 => (Stage::PartOne, S::part_one),
+            2 => (Stage::PartTwo, S::part_two),
+            p => return Err(format!("invalid part {}", p).into()),
+        };
+
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            black_box(run(black_box(&parsed))?);
+            samples.push(start.elapsed());
+        }
+        results.push(BenchResult::from_samples(day, input_name, stage, samples));
+    }
+
+    Ok(results)
+}
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub iterations: usize,
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchReport {
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("iterations".into(), self.iterations.into()),
+            (
+                "results".into(),
+                Json::Array(self.results.iter().map(|r| r.to_json()).collect()),
+            ),
+        ])
+    }
+
+    pub fn parse(data: &str) -> Result<Self, Box<dyn Error>> {
+        let json = Json::parse(data)?;
+
+        let iterations = json
+            .get("iterations")
+            .and_then(|i| i.as_f64())
+            .ok_or("report is missing iterations")? as usize;
+
+        let results = json
+            .get("results")
+            .and_then(|r| r.as_array())
+            .ok_or("report is missing results")?
+            .iter()
+            .map(|r| BenchResult::from_json(r).ok_or("invalid bench result"))
+            .collect::<Result<_, _>>()?;
+
+        Ok(BenchReport {
+            iterations,
+            results,
+        })
+    }
+
+    pub fn table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .results
+            .iter()
+            .map(|r| {
+                vec![
+                    r.day.to_string(),
+                    r.input.clone(),
+                    r.stage.name().to_string(),
+                    format_ns(r.min_ns),
+                    format_ns(r.median_ns),
+                    format_ns(r.max_ns),
+                ]
+            })
+            .collect();
+
+        table::render(&["day", "input", "stage", "min", "median", "max"], &rows)
+    }
+}
+
+/// A stage's median compared to the same stage in a saved baseline.
+#[derive(Debug)]
+pub struct Comparison<'a> {
+    pub current: &'a BenchResult,
+    pub baseline_ns: u64,
+    pub change_pct: f64,
+    pub regressed: bool,
+}
+
+/// Matches every result against the baseline by day, input and stage and
+/// flags the ones whose median got slower by more than `threshold_pct`.
+pub fn compare<'a>(
+    current: &'a BenchReport,
+    baseline: &BenchReport,
+    threshold_pct: f64,
+) -> Vec<Comparison<'a>> {
+    current
+        .results
+        .iter()
+        .filter_map(|r| {
+            let base = baseline
+                .results
+                .iter()
+                .find(|b| b.day == r.day && b.input == r.input && b.stage == r.stage)?;
+
+            let change_pct = if base.median_ns == 0 {
+                0.0
+            } else {
+                (r.median_ns as f64 / base.median_ns as f64 - 1.0) * 100.0
+            };
+
+            Some(Comparison {
+                current: r,
+                baseline_ns: base.median_ns,
+                change_pct,
+                regressed: change_pct > threshold_pct,
+            })
+        })
+        .collect()
+}
+
+pub fn comparison_table(comparisons: &[Comparison]) -> String {
+    let rows: Vec<Vec<String>> = comparisons
+        .iter()
+        .map(|c| {
+            vec![
+                c.current.day.to_string(),
+                c.current.input.clone(),
+                c.current.stage.name().to_string(),
+                format_ns(c.baseline_ns),
+                format_ns(c.current.median_ns),
+                format!("{:+.1}%", c.change_pct),
+                if c.regressed { "SLOWER" } else { "ok" }.to_string(),
+            ]
+        })
+        .collect();
+
+    table::render(
+        &[
+            "day", "input", "stage", "baseline", "median", "change", "status",
+        ],
+        &rows,
+    )
+}
+
+fn format_ns(ns: u64) -> String {
+    match ns {
+        n if n >= 1_000_000_000 => format!("{:.2}s", n as f64 / 1e9),
+        n if n >= 1_000_000 => format!("{:.2}ms", n as f64 / 1e6),
+        n if n >= 1_000 => format!("{:.2}µs", n as f64 / 1e3),
+        n => format!("{}ns", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(day: u8, stage: Stage, median_ns: u64) -> BenchResult {
+        BenchResult {
+            day,
+            input: format!("{}/input.txt", day),
+            stage,
+            min_ns: median_ns / 2,
+            median_ns,
+            max_ns: median_ns * 2,
+        }
+    }
+
+    fn report(results: Vec<BenchResult>) -> BenchReport {
+        BenchReport {
+            iterations: 10,
+            results,
+        }
+    }
+
+    #[test]
+    fn parses_a_saved_report() {
+        let saved = r#"{
+            "iterations": 5,
+            "results": [
+                {"day": 3, "input": "3/input.txt", "stage": "part_two",
+                 "min_ns": 900, "median_ns": 1000, "max_ns": 1500}
+            ]
+        }"#;
+
+        let report = BenchReport::parse(saved).unwrap();
+        let r = &report.results[0];
+
+        assert_eq!(report.iterations, 5);
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(
+            (r.day, r.input.as_str(), r.stage),
+            (3, "3/input.txt", Stage::PartTwo)
+        );
+        assert_eq!((r.min_ns, r.median_ns, r.max_ns), (900, 1000, 1500));
+    }
+
+    #[test]
+    fn reports_round_trip() {
+        let saved = report(vec![
+            result(1, Stage::Parse, 120),
+            result(1, Stage::PartOne, 45_000),
+            result(9, Stage::PartTwo, 3_000_000_000),
+        ]);
+
+        let parsed = BenchReport::parse(&saved.to_json().to_string()).unwrap();
+
+        assert_eq!(parsed.iterations, saved.iterations);
+        assert_eq!(parsed.to_json(), saved.to_json());
+    }
+
+    #[test]
+    fn rejects_malformed_reports() {
+        for text in [
+            "{",
+            r#"{"results": []}"#,
+            r#"{"iterations": 1}"#,
+            r#"{"iterations": 1, "results": [{"day": 1}]}"#,
+            r#"{"iterations": 1, "results": [{"day": 1, "input": "x", "stage": "part_three",
+                "min_ns": 1, "median_ns": 1, "max_ns": 1}]}"#,
+        ] {
+            assert!(BenchReport::parse(text).is_err(), "{} parsed", text);
+        }
+    }
+
+    #[test]
+    fn flags_slowdowns_over_the_threshold() {
+        let baseline = report(vec![
+            result(1, Stage::Parse, 1000),
+            result(1, Stage::PartOne, 1000),
+            result(1, Stage::PartTwo, 0),
+        ]);
+        let current = report(vec![
+            // 15% slower, past a 10% threshold
+            result(1, Stage::Parse, 1150),
+            // 5% slower, within it
+            result(1, Stage::PartOne, 1050),
+            // Nothing to compare against a zero median
+            result(1, Stage::PartTwo, 500),
+            // Not in the baseline at all
+            result(2, Stage::Parse, 1000),
+        ]);
+
+        let comparisons = compare(&current, &baseline, 10.0);
+        let summary: Vec<(Stage, u64, bool)> = comparisons
+            .iter()
+            .map(|c| (c.current.stage, c.baseline_ns, c.regressed))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (Stage::Parse, 1000, true),
+                (Stage::PartOne, 1000, false),
+                (Stage::PartTwo, 0, false),
+            ]
+        );
+        assert!((comparisons[0].change_pct - 15.0).abs() < 1e-9);
+        assert!((comparisons[1].change_pct - 5.0).abs() < 1e-9);
+        assert_eq!(comparisons[2].change_pct, 0.0);
+
+        // Faster is never a regression, and a looser threshold lets 15% pass
+        let faster = report(vec![result(1, Stage::Parse, 500)]);
+        assert!(!compare(&faster, &baseline, 10.0)[0].regressed);
+        assert!(!compare(&current, &baseline, 20.0)[0].regressed);
+    }
+}
@@ -0,0 +1,193 @@
+// Advent of code 2023: new day generator
+// Author: @alisinabh
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: scaffold <day> [--author <name>]";
+
+const DEFAULT_AUTHOR: &str = "@alisinabh";
+
+const TEMPLATE: &str = r#"// Advent of code: Day {day}
+// Author: {author}
+
+use std::error::Error;
+
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Vec<String>;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(error::lines(input).map(|l| l.text.to_string()).collect())
+    }
+
+    fn part_one(_input: &Self::Input) -> Result<u64, Box<dyn Error>> {
+        Err("part one is not solved yet".into())
+    }
+
+    fn part_two(_input: &Self::Input) -> Result<u64, Box<dyn Error>> {
+        Err("part two is not solved yet".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("test-input.txt");
+
+    #[test]
+    fn parses_example() {
+        Day{day}::parse(EXAMPLE).unwrap();
+    }
+
+    #[test]
+    #[ignore = "fill in the example answer"]
+    fn part_one_example() {
+        let input = Day{day}::parse(EXAMPLE).unwrap();
+        assert_eq!(Day{day}::part_one(&input).unwrap(), 0);
+    }
+
+    #[test]
+    #[ignore = "fill in the example answer"]
+    fn part_two_example() {
+        let input = Day{day}::parse(EXAMPLE).unwrap();
+        assert_eq!(Day{day}::part_two(&input).unwrap(), 0);
+    }
+}
+"#;
+
+/// Writes `<root>/N/solution.rs` and the empty input files, refusing to
+/// touch a day that already has a solution.
+fn create_day(root: &Path, day: u8, author: &str) -> Result<(), Box<dyn Error>> {
+    let dir = root.join(day.to_string());
+    let solution = dir.join("solution.rs");
+
+    if solution.exists() {
+        return Err(format!("{} already exists", solution.display()).into());
+    }
+
+    fs::create_dir_all(&dir)?;
+
+    let source = TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{author}", author);
+    fs::write(&solution, source)?;
+    println!("created {}", solution.display());
+
+    for name in ["input.txt", "test-input.txt"] {
+        let path = dir.join(name);
+        if !path.exists() {
+            fs::write(&path, "")?;
+            println!("created {}", path.display());
+        }
+    }
+
+    let answers = dir.join("answers.txt");
+    if !answers.exists() {
+        fs::write(&answers, "# input part answer\n")?;
+        println!("created {}", answers.display());
+    }
+
+    Ok(())
+}
+
+/// Declares the day's module in `src/lib.rs` and adds it to the `days!` table.
+fn register_day(root: &Path, day: u8) -> Result<(), Box<dyn Error>> {
+    let path = root.join("src/lib.rs");
+    let lib = fs::read_to_string(&path)?;
+
+    let module = format!(
+        "#[path = \"../{day}/solution.rs\"]\npub mod day{day};\n",
+        day = day
+    );
+    let entry = format!("    {day} => day{day}::Day{day},\n", day = day);
+
+    if lib.contains(&module) {
+        return Err(format!("day {} is already registered in {}", day, path.display()).into());
+    }
+
+    // Keep the declarations in the order rustfmt sorts them, by module name
+    let name = format!("day{}", day);
+    let insert_at = lib
+        .match_indices("#[path = ")
+        .find(|&(i, _)| {
+            lib[i..]
+                .lines()
+                .nth(1)
+                .and_then(|l| l.strip_prefix("pub mod "))
+                .is_some_and(|m| m.trim_end_matches(';') > name.as_str())
+        })
+        .map(|(i, _)| i)
+        .or_else(|| {
+            let last = lib.rfind("pub mod day")?;
+            lib[last..].find('\n').map(|end| last + end + 1)
+        })
+        .ok_or("no day modules found in src/lib.rs")?;
+
+    let table_start = lib.find("days! {").ok_or("no days! table in src/lib.rs")?;
+    let table_end = lib[table_start..]
+        .find("\n}")
+        .map(|i| table_start + i + 1)
+        .ok_or("unterminated days! table in src/lib.rs")?;
+
+    let mut updated = String::with_capacity(lib.len() + module.len() + entry.len());
+    updated.push_str(&lib[..insert_at]);
+    updated.push_str(&module);
+    updated.push_str(&lib[insert_at..table_end]);
+    updated.push_str(&entry);
+    updated.push_str(&lib[table_end..]);
+
+    fs::write(&path, updated)?;
+    println!("registered day {} in {}", day, path.display());
+
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut day: Option<u8> = None;
+    let mut author = DEFAULT_AUTHOR.to_string();
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--author" => author = args.next().ok_or("missing value for --author")?.to_string(),
+            n if day.is_none() && !n.starts_with('-') => day = Some(n.parse()?),
+            _ => return Err(format!("unknown argument {}\n{}", arg, USAGE).into()),
+        }
+    }
+
+    let day = day.ok_or(USAGE)?;
+
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {} is not between 1 and 25", day).into());
+    }
+
+    let root = Path::new(".");
+
+    create_day(root, day, &author)?;
+    register_day(root, day)?;
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args().collect();
+
+    match run(&args[1..]) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::env;
+use std::error::Error;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Settings for talking to the puzzle website and tuning the solutions.
+///
+/// Read from a `key = value` file (`$AOC_CONFIG`, else `.aoc` in the current
+/// directory, else `~/.config/aoc/config`) with `AOC_SESSION` and
+/// `AOC_BASE_URL` in the environment taking precedence.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    /// Spelled digits for day 1, in the format of `day1::Vocabulary`.
+    pub digit_words: Option<String>,
+    /// Cube limits for day 2, in the format of `day2::Bag`; several bags are
+    /// separated by `;`.
+    pub bag: Option<String>,
+    /// Gear rule for day 3, in the format of `day3::GearRule`.
+    pub gears: Option<String>,
+    /// Neighbourhood for day 3, in the format of `day3::Neighbourhood`.
+    pub neighbourhood: Option<String>,
+    /// Whether day 3 adjacency wraps around the edges of the schematic.
+    pub wrap: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            digit_words: None,
+            bag: None,
+            gears: None,
+            neighbourhood: None,
+            wrap: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(data: &str) -> Result<Self, Box<dyn Error>> {
+        let mut config = Config::default();
+
+        for (n, line) in data.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("config line {}: expected `key = value`", n + 1).into());
+            };
+
+            let value = value.trim().trim_matches('"').to_string();
+
+            match key.trim() {
+                "session" => config.session = Some(value),
+                "base_url" => config.base_url = value,
+                "digit_words" => config.digit_words = Some(value),
+                "bag" => config.bag = Some(value),
+                "gears" => config.gears = Some(value),
+                "neighbourhood" => config.neighbourhood = Some(value),
+                "wrap" => {
+                    config.wrap = value
+                        .parse()
+                        .map_err(|_| format!("config line {}: wrap must be true or false", n + 1))?
+                }
+                key => return Err(format!("config line {}: unknown key `{}`", n + 1, key).into()),
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let mut config = match Self::path() {
+            Some(path) => Self::parse(&read_to_string(&path)?)
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+            None => Config::default(),
+        };
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    fn path() -> Option<PathBuf> {
+        if let Ok(path) = env::var("AOC_CONFIG") {
+            return Some(path.into());
+        }
+
+        let local = PathBuf::from(".aoc");
+        if local.is_file() {
+            return Some(local);
+        }
+
+        let home = PathBuf::from(env::var("HOME").ok()?).join(".config/aoc/config");
+        home.is_file().then_some(home)
+    }
+
+    pub fn session(&self) -> Result<&str, Box<dyn Error>> {
+        self.session.as_deref().ok_or_else(|| {
+            "no session token, set AOC_SESSION or `session` in the config file".into()
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token or separator that has to be there is missing or malformed.
+    Expected(&'static str),
+    /// A number could not be parsed; holds the reason given by `FromStr`.
+    InvalidNumber(String),
+    /// A character that has no meaning for the puzzle.
+    InvalidCharacter(char),
+    /// The input ended before a required section.
+    UnexpectedEnd(&'static str),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expected(what) => write!(f, "expected {}", what),
+            Self::InvalidNumber(reason) => write!(f, "invalid number: {}", reason),
+            Self::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Self::UnexpectedEnd(what) => write!(f, "input ended before {}", what),
+        }
+    }
+}
+
+/// A parse failure pointing at the offending part of the input.
+///
+/// `line` and `column` are 1-based and the column counts characters, not
+/// bytes. `snippet` is the whole offending line and `width` the number of
+/// characters to underline starting at `column`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub snippet: String,
+}
+
+impl ParseError {
+    /// Error for input that ended early, placed just after the last line.
+    pub fn at_end(input: &str, kind: ParseErrorKind) -> Self {
+        ParseError {
+            kind,
+            file: None,
+            line: input.lines().count() + 1,
+            column: 1,
+            width: 1,
+            snippet: String::new(),
+        }
+    }
+
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<input>");
+        writeln!(f, "{}:{}:{}: {}", file, self.line, self.column, self.kind)?;
+
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        // Keep tabs so the caret lines up with what the terminal shows
+        let indent: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            indent,
+            "^".repeat(self.width.max(1))
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Attaches the input file name to an error, placing it in the location of
+/// parse errors and in front of the message of anything else.
+pub fn with_file(err: Box<dyn Error>, file: &str) -> Box<dyn Error> {
+    match err.downcast::<ParseError>() {
+        Ok(e) => Box::new(e.in_file(file)),
+        Err(e) => format!("{}: {}", file, e).into(),
+    }
+}
+
+/// One line of the puzzle input along with its 1-based line number.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+impl<'a> Line<'a> {
+    /// Error pointing at `at`, which has to be a slice of this line; anything
+    /// else underlines the whole line.
+    pub fn error(&self, kind: ParseErrorKind, at: &str) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize).wrapping_sub(start);
+
+        let (column, width) = if offset <= self.text.len() && offset + at.len() <= self.text.len() {
+            (self.text[..offset].chars().count() + 1, at.chars().count())
+        } else {
+            (1, self.text.chars().count())
+        };
+
+        self.error_at(kind, column, width)
+    }
+
+    /// Error at a 1-based character column.
+    pub fn error_at(&self, kind: ParseErrorKind, column: usize, width: usize) -> ParseError {
+        ParseError {
+            kind,
+            file: None,
+            line: self.number,
+            column,
+            width,
+            snippet: self.text.to_string(),
+        }
+    }
+
+    /// Error just past the end of the line, for things missing at its end.
+    pub fn error_at_end(&self, kind: ParseErrorKind) -> ParseError {
+        self.error(kind, &self.text[self.text.len()..])
+    }
+
+    /// Parses `s`, a slice of this line, as a number.
+    pub fn number<T>(&self, s: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        s.parse()
+            .map_err(|e: T::Err| self.error(ParseErrorKind::InvalidNumber(e.to_string()), s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caret_keeps_tabs_in_the_indent() {
+        let text = "Game\t7: 3 bleu";
+        let line = Line { number: 12, text };
+        let err = line
+            .error(ParseErrorKind::Expected("a colour"), &text[10..])
+            .in_file("2/input.txt");
+
+        assert_eq!(
+            err.to_string(),
+            concat!(
+                "2/input.txt:12:11: expected a colour\n",
+                "   |\n",
+                "12 | Game\t7: 3 bleu\n",
+                "   |     \t     ^^^^",
+            )
+        );
+    }
+
+    #[test]
+    fn error_at_end_shows_one_caret() {
+        let line = Line {
+            number: 3,
+            text: "Card 1: 41 48",
+        };
+        let err = line.error_at_end(ParseErrorKind::UnexpectedEnd("the separator"));
+
+        assert_eq!((err.column, err.width), (14, 0));
+        assert_eq!(
+            err.to_string(),
+            concat!(
+                "<input>:3:14: input ended before the separator\n",
+                "  |\n",
+                "3 | Card 1: 41 48\n",
+                "  |              ^",
+            )
+        );
+    }
+}
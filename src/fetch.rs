@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::Client;
+use crate::YEAR;
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the personal input of a day into `<root>/N/input.txt`, unless
+/// a non-empty copy is already there.
+pub fn fetch_input(client: &Client, root: &Path, day: u8) -> Result<Fetched, Box<dyn Error>> {
+    let dir = root.join(day.to_string());
+    let path = dir.join("input.txt");
+
+    if fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let response = client.get(&format!("/{}/day/{}/input", YEAR, day))?;
+
+    if response.status != 200 {
+        return Err(format!(
+            "fetching day {} input failed with status {}: {}",
+            day,
+            response.status,
+            response.body.trim()
+        )
+        .into());
+    }
+
+    fs::create_dir_all(&dir)?;
+    fs::write(&path, response.body)?;
+
+    Ok(Fetched::Downloaded(path))
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Puzzle input along with the name it is reported under.
+#[derive(Debug, Clone)]
+pub struct PuzzleInput {
+    pub label: String,
+    pub text: String,
+}
+
+pub const STDIN: &str = "-";
+
+/// Finds the file for an input argument of a day: the path as given when it
+/// exists, otherwise the same name inside the day's directory, so
+/// `test-input.txt` works from the repository root.
+pub fn resolve(day: u8, arg: &str) -> String {
+    let in_day_dir = Path::new(&day.to_string()).join(arg);
+
+    if !Path::new(arg).exists() && in_day_dir.exists() {
+        in_day_dir.to_string_lossy().to_string()
+    } else {
+        arg.to_string()
+    }
+}
+
+pub fn default_path(day: u8) -> String {
+    format!("{}/input.txt", day)
+}
+
+/// Reads every input argument of a day, `-` being stdin. Without arguments
+/// the day's `N/input.txt` is read.
+pub fn read_inputs(day: u8, args: &[String]) -> Result<Vec<PuzzleInput>, Box<dyn Error>> {
+    if args.is_empty() {
+        return Ok(vec![read_input(day, &default_path(day))?]);
+    }
+
+    if args.iter().filter(|a| *a == STDIN).count() > 1 {
+        return Err("stdin can only be read once".into());
+    }
+
+    args.iter().map(|arg| read_input(day, arg)).collect()
+}
+
+pub fn read_input(day: u8, arg: &str) -> Result<PuzzleInput, Box<dyn Error>> {
+    if arg == STDIN {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+
+        return Ok(PuzzleInput {
+            label: "<stdin>".to_string(),
+            text,
+        });
+    }
+
+    let path = resolve(day, arg);
+    let text = read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+
+    Ok(PuzzleInput { label: path, text })
+}
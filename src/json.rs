@@ -0,0 +1,407 @@
+use std::error::Error;
+use std::fmt;
+
+/// Minimal JSON value used for reports and saved baselines.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn parse(data: &str) -> Result<Json, Box<dyn Error>> {
+        let mut parser = Parser {
+            chars: data.chars().collect(),
+            pos: 0,
+        };
+
+        let value = parser.value()?;
+        parser.skip_whitespace();
+
+        match parser.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected `{}` at {}", c, parser.pos).into()),
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(n: u64) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<u8> for Json {
+    fn from(n: u8) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Box<dyn Error>> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!(
+                "expected `{}` but found `{}` at {}",
+                expected,
+                c,
+                self.pos - 1
+            )
+            .into()),
+            None => Err(format!("expected `{}` but input ended", expected).into()),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, Box<dyn Error>> {
+        for c in word.chars() {
+            self.expect(c)?;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, Box<dyn Error>> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('n') => self.literal("null", Json::Null),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected `{}` at {}", c, self.pos).into()),
+            None => Err("unexpected end of input".into()),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, Box<dyn Error>> {
+        let start = self.pos;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || "+-.eE".contains(c)) {
+            self.pos += 1;
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+
+        Ok(Json::Number(text.parse()?))
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        self.expect('"')?;
+
+        let mut s = String::new();
+
+        loop {
+            match self.next().ok_or("unterminated string")? {
+                '"' => return Ok(s),
+                '\\' => match self.next().ok_or("unterminated string")? {
+                    'n' => s.push('\n'),
+                    'r' => s.push('\r'),
+                    't' => s.push('\t'),
+                    'b' => s.push('\u{8}'),
+                    'f' => s.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex4()?;
+
+                        // Characters outside the BMP come as a surrogate pair
+                        if (0xD800..0xDC00).contains(&code)
+                            && self.chars[self.pos..].starts_with(&['\\', 'u'])
+                        {
+                            self.pos += 2;
+                            code = match self.hex4()? {
+                                low @ 0xDC00..=0xDFFF => {
+                                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                                }
+                                _ => char::REPLACEMENT_CHARACTER as u32,
+                            };
+                        }
+
+                        s.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    c => s.push(c),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// The four hex digits of a `\\u` escape.
+    fn hex4(&mut self) -> Result<u32, Box<dyn Error>> {
+        let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+
+        if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("invalid \\u escape at {}", self.pos).into());
+        }
+
+        self.pos += 4;
+        Ok(u32::from_str_radix(&hex, 16)?)
+    }
+
+    fn array(&mut self) -> Result<Json, Box<dyn Error>> {
+        self.expect('[')?;
+
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(items)),
+                _ => return Err(format!("expected `,` or `]` at {}", self.pos - 1).into()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, Box<dyn Error>> {
+        self.expect('{')?;
+
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(fields)),
+                _ => return Err(format!("expected `,` or `}}` at {}", self.pos - 1).into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(fields: &[(&str, Json)]) -> Json {
+        Json::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_values() {
+        let json = Json::parse(
+            r#" { "a": [1, -2.5, 3e2], "b": {"c": null},
+            "d": true, "e": false, "f": "x", "g": [], "h": {} } "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            json,
+            object(&[
+                (
+                    "a",
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-2.5),
+                        Json::Number(300.0)
+                    ])
+                ),
+                ("b", object(&[("c", Json::Null)])),
+                ("d", Json::Bool(true)),
+                ("e", Json::Bool(false)),
+                ("f", "x".into()),
+                ("g", Json::Array(vec![])),
+                ("h", object(&[])),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let json = Json::parse(r#""q\"b\\s\/\n\r\t\b\f\u00e9\ud83c\udf84""#).unwrap();
+
+        assert_eq!(json.as_str(), Some("q\"b\\s/\n\r\t\u{8}\u{c}é🎄"));
+        // A lone surrogate can't be a character
+        assert_eq!(
+            Json::parse(r#""\ud83c!""#).unwrap().as_str(),
+            Some("\u{fffd}!")
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let value = object(&[
+            (
+                "text",
+                "tab\there \"quoted\" back\\slash\nnew line\u{1}".into(),
+            ),
+            (
+                "numbers",
+                Json::Array(vec![Json::Number(0.5), 123456789u64.into()]),
+            ),
+            ("nested", object(&[("empty", Json::Array(vec![]))])),
+        ]);
+
+        let text = value.to_string();
+
+        assert!(text.contains(r#""tab\there \"quoted\" back\\slash\nnew line\u0001""#));
+        assert_eq!(Json::parse(&text).unwrap(), value);
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        for text in [
+            "",
+            "{",
+            "[1,]",
+            "[1 2]",
+            r#"{"a" 1}"#,
+            r#"{"a": 1,}"#,
+            "{1: 2}",
+            "tru",
+            "nul",
+            r#""open"#,
+            r#""\u12""#,
+            r#""\u+123""#,
+            "1 2",
+            "--1",
+            "@",
+        ] {
+            assert!(Json::parse(text).is_err(), "{:?} parsed", text);
+        }
+    }
+}
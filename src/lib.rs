@@ -0,0 +1,83 @@
+// Advent of code 2023
+// Author: @alisinabh
+
+use std::error::Error;
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod fetch;
+pub mod input;
+pub mod json;
+pub mod output;
+pub mod solution;
+pub mod submit;
+pub mod table;
+
+#[path = "../1/solution.rs"]
+pub mod day1;
+#[path = "../2/solution.rs"]
+pub mod day2;
+#[path = "../3/solution.rs"]
+pub mod day3;
+#[path = "../4/solution.rs"]
+pub mod day4;
+#[path = "../5/solution.rs"]
+pub mod day5;
+#[path = "../6/solution.rs"]
+pub mod day6;
+#[path = "../7/solution.rs"]
+pub mod day7;
+#[path = "../8/solution.rs"]
+pub mod day8;
+#[path = "../9/solution.rs"]
+pub mod day9;
+
+pub const YEAR: u16 = 2023;
+
+/// Parts of every day's puzzle.
+pub const PARTS: &[u8] = &[1, 2];
+
+// Generates the list of days and the functions dispatching a day number to
+// its `Solution`, so a new day only needs one entry in the table below.
+macro_rules! days {
+    ($($day:literal => $module:ident::$solution:ident,)*) => {
+        pub const DAYS: &[u8] = &[$($day),*];
+
+        /// Runs the given part of a day against the puzzle input and returns the answer.
+        pub fn solve(day: u8, part: u8, input: &str) -> Result<String, Box<dyn Error>> {
+            match day {
+                $($day => solution::solve::<$module::$solution>(part, input),)*
+                d => Err(format!("no solution for day {}", d).into()),
+            }
+        }
+
+        /// Times the parse stage and every solved part of a day over the given input.
+        pub fn bench(
+            day: u8,
+            input_name: &str,
+            input: &str,
+            iterations: usize,
+        ) -> Result<Vec<bench::BenchResult>, Box<dyn Error>> {
+            match day {
+                $($day => bench::measure::<$module::$solution>(day, input_name, input, PARTS, iterations),)*
+                d => Err(format!("no solution for day {}", d).into()),
+            }
+        }
+    };
+}
+
+days! {
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    7 => day7::Day7,
+    8 => day8::Day8,
+    9 => day9::Day9,
+}
@@ -0,0 +1,628 @@
+// Advent of code 2023: runner
+// Author: @alisinabh
+
+use std::env;
+use std::error::Error;
+use std::fs::{self, read_to_string};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use aoc::answers::{self, Report};
+use aoc::bench::{self, BenchReport};
+use aoc::client::Client;
+use aoc::config::Config;
+use aoc::day1::{self, Calibration, Explained, Vocabulary};
+use aoc::day2::{self, Bag, Day2, Feasibility, Inference};
+use aoc::day3::{self, Adjacency, Day3, GearRule, GraphFormat, Neighbourhood, RenderFormat};
+use aoc::error::{self, ParseError};
+use aoc::fetch::{self, Fetched};
+use aoc::input::{self, PuzzleInput};
+use aoc::output::{Format, Record};
+use aoc::solution::Solution;
+use aoc::submit::{self, Verdict};
+
+const USAGE: &str = "usage:
+    aoc run --day <N> [--part <1|2>] [--format <text|json|tsv>] [--words <vocabulary>]
+            [--explain] [--bag <colour=count,...>]... [--graph <json|dot>]
+            [--gears <symbols:neighbours:product|sum|max>]
+            [--neighbourhood <4|8|chebyshev=<radius>>] [--wrap] [--validate]
+            [--render <terminal|svg>] [<input>... | -]
+    aoc verify [--day <N>]
+    aoc bench [--day <N> [--input <path>]] [--iterations <N>] [--output <report.json>]
+              [--baseline <report.json>] [--threshold <percent>]
+    aoc fetch --day <N> [--base-url <url>]
+    aoc submit --day <N> --part <1|2> [--base-url <url>]";
+
+#[derive(Debug)]
+struct RunArgs {
+    day: u8,
+    parts: Vec<u8>,
+    inputs: Vec<String>,
+    format: Format,
+    words: Option<String>,
+    explain: bool,
+    bags: Vec<String>,
+    graph: Option<GraphFormat>,
+    gears: Option<String>,
+    neighbourhood: Option<String>,
+    wrap: bool,
+    validate: bool,
+    render: Option<RenderFormat>,
+}
+
+impl RunArgs {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut day = None;
+        let mut parts = None;
+        let mut inputs = Vec::new();
+        let mut format = Format::Text;
+        let mut words = None;
+        let mut explain = false;
+        let mut bags = Vec::new();
+        let mut graph = None;
+        let mut gears = None;
+        let mut neighbourhood = None;
+        let mut wrap = false;
+        let mut validate = false;
+        let mut render = None;
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+            match arg.as_str() {
+                "--day" | "-d" => day = Some(value()?.parse()?),
+                "--part" | "-p" => parts = Some(vec![value()?.parse()?]),
+                "--input" | "-i" => inputs.push(value()?.to_string()),
+                "--format" | "-f" => format = value()?.parse()?,
+                "--words" | "-w" => words = Some(value()?.to_string()),
+                "--explain" | "-e" => explain = true,
+                "--bag" | "-b" => bags.push(value()?.to_string()),
+                "--graph" | "-g" => graph = Some(value()?.parse()?),
+                "--gears" => gears = Some(value()?.to_string()),
+                "--neighbourhood" | "-n" => neighbourhood = Some(value()?.to_string()),
+                "--wrap" => wrap = true,
+                "--validate" => validate = true,
+                "--render" | "-r" => render = Some(value()?.parse()?),
+                input::STDIN => inputs.push(arg.to_string()),
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown argument {}\n{}", arg, USAGE).into())
+                }
+                _ => inputs.push(arg.to_string()),
+            }
+        }
+
+        let day = day.ok_or("missing --day")?;
+
+        Ok(RunArgs {
+            day,
+            parts: parts.unwrap_or_else(|| aoc::PARTS.to_vec()),
+            inputs,
+            format,
+            words,
+            explain,
+            bags,
+            graph,
+            gears,
+            neighbourhood,
+            wrap,
+            validate,
+            render,
+        })
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = RunArgs::parse(args)?;
+    let tuning = Tuning::load(&args)?;
+
+    if args.explain {
+        return explain(&args, &tuning);
+    }
+
+    if args.validate {
+        return validate_schematics(&args);
+    }
+
+    if let Some(format) = args.graph {
+        let Tuning::Day3(_, adjacency) = tuning else {
+            return Err("--graph only applies to day 3".into());
+        };
+
+        return export_graph(&args, format, adjacency);
+    }
+
+    if let Some(format) = args.render {
+        let Tuning::Day3(rule, adjacency) = &tuning else {
+            return Err("--render only applies to day 3".into());
+        };
+
+        return render(&args, format, rule, *adjacency);
+    }
+
+    let mut records = Vec::new();
+    let mut failures = 0;
+
+    // Keep going after a failing input so every file still gets its answers
+    for input in input::read_inputs(args.day, &args.inputs)? {
+        // Each warning along with the parts it came up in
+        let mut warnings: Vec<(ParseError, Vec<u8>)> = Vec::new();
+
+        for &part in &args.parts {
+            let start = Instant::now();
+
+            match tuning.solve(args.day, part, &input) {
+                Ok((answer, found)) => {
+                    records.push(Record {
+                        day: args.day,
+                        part,
+                        input: input.label.clone(),
+                        answer,
+                        duration_ns: start.elapsed().as_nanos() as u64,
+                    });
+
+                    for warning in found {
+                        match warnings.iter_mut().find(|(seen, _)| *seen == warning) {
+                            Some((_, parts)) => parts.push(part),
+                            None => warnings.push((warning, vec![part])),
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("error: {}", error::with_file(e, &input.label));
+                    failures += 1;
+                }
+            }
+        }
+
+        for (warning, parts) in warnings {
+            let warning = warning.in_file(&input.label);
+
+            if parts.len() == args.parts.len() {
+                eprintln!("warning: {}", warning);
+            } else {
+                let parts: Vec<String> = parts.iter().map(u8::to_string).collect();
+                eprintln!("warning (part {}): {}", parts.join(", "), warning);
+            }
+        }
+    }
+
+    print!("{}", args.format.render(&records));
+
+    if failures > 0 {
+        return Err(format!("{} runs failed", failures).into());
+    }
+
+    Ok(())
+}
+
+/// Prints how the answers came about: for day 1 how every line got its
+/// calibration value, for day 2 which games each bag allows and how likely
+/// each bag is.
+fn explain(args: &RunArgs, tuning: &Tuning) -> Result<(), Box<dyn Error>> {
+    for input in input::read_inputs(args.day, &args.inputs)? {
+        match tuning {
+            Tuning::Day1(vocabulary) => {
+                for &part in &args.parts {
+                    let mode = Calibration::for_part(part, vocabulary)?;
+                    let explained = day1::explain(&input.text, mode);
+
+                    println!("day 1 part {} ({}):", part, input.label);
+                    print!("{}", Explained(&explained));
+                }
+            }
+            Tuning::Day2(bags) => {
+                let games = Day2::parse(&input.text).map_err(|e| e.in_file(&input.label))?;
+
+                println!("day 2 ({}):", input.label);
+
+                for bag in bags {
+                    print!("{}", Feasibility::check(&games, bag));
+                }
+
+                print!("{}", Inference::infer(&games, bags));
+            }
+            _ => return Err("--explain only applies to days 1 and 2".into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists every problem found in the day 3 schematics.
+fn validate_schematics(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    if args.day != 3 {
+        return Err("--validate only applies to day 3".into());
+    }
+
+    let mut problems = 0;
+
+    for input in input::read_inputs(args.day, &args.inputs)? {
+        let issues = day3::validate(&input.text);
+
+        if issues.is_empty() {
+            println!("{}: ok", input.label);
+        }
+
+        for issue in &issues {
+            println!("{}", issue.clone().in_file(&input.label));
+        }
+
+        problems += issues.len();
+    }
+
+    if problems > 0 {
+        return Err(format!("{} problems found", problems).into());
+    }
+
+    Ok(())
+}
+
+/// Prints which part numbers of the day 3 schematics touch which symbols.
+fn export_graph(
+    args: &RunArgs,
+    format: GraphFormat,
+    adjacency: Adjacency,
+) -> Result<(), Box<dyn Error>> {
+    for input in input::read_inputs(args.day, &args.inputs)? {
+        let engine = Day3::parse(&input.text)
+            .map_err(|e| e.in_file(&input.label))?
+            .with_adjacency(adjacency);
+        let graph = engine.graph();
+
+        match format {
+            GraphFormat::Json => println!("{}", graph.to_json()),
+            GraphFormat::Dot => print!("{}", graph.to_dot()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws the day 3 schematics with their part numbers and gears marked.
+fn render(
+    args: &RunArgs,
+    format: RenderFormat,
+    rule: &GearRule,
+    adjacency: Adjacency,
+) -> Result<(), Box<dyn Error>> {
+    // Colours only go to a terminal, and not when NO_COLOR is set
+    let colour = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
+
+    for input in input::read_inputs(args.day, &args.inputs)? {
+        let engine = Day3::parse(&input.text)
+            .map_err(|e| e.in_file(&input.label))?
+            .with_adjacency(adjacency);
+
+        match format {
+            RenderFormat::Terminal => print!("{}", engine.render_terminal(rule, colour)),
+            RenderFormat::Svg => print!("{}", engine.render_svg(rule)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Day specific settings, taken from their flag or else the config file.
+enum Tuning {
+    Default,
+    /// Spelled digits for day 1, English unless `--words` says otherwise.
+    Day1(Vocabulary),
+    /// Bags for day 2, the puzzle's bag unless `--bag` says otherwise. Only
+    /// `--explain` takes more than one.
+    Day2(Vec<Bag>),
+    /// Gear rule and adjacency for day 3, the puzzle's unless `--gears`,
+    /// `--neighbourhood` or `--wrap` say otherwise.
+    Day3(GearRule, Adjacency),
+}
+
+impl Tuning {
+    fn load(args: &RunArgs) -> Result<Self, Box<dyn Error>> {
+        if args.words.is_some() && args.day != 1 {
+            return Err("--words only applies to day 1".into());
+        }
+
+        if !args.bags.is_empty() && args.day != 2 {
+            return Err("--bag only applies to day 2".into());
+        }
+
+        if (args.gears.is_some() || args.neighbourhood.is_some() || args.wrap) && args.day != 3 {
+            return Err("--gears, --neighbourhood and --wrap only apply to day 3".into());
+        }
+
+        match args.day {
+            1 => {
+                let words = match &args.words {
+                    Some(words) => Some(words.clone()),
+                    None => Config::load()?.digit_words,
+                };
+
+                Ok(Tuning::Day1(match words {
+                    Some(words) => words.parse()?,
+                    None => Vocabulary::english(),
+                }))
+            }
+            2 => {
+                let mut bags = Vec::new();
+
+                for bag in &args.bags {
+                    bags.extend(Bag::parse_list(bag)?);
+                }
+
+                if bags.is_empty() {
+                    bags = match Config::load()?.bag {
+                        Some(bag) => Bag::parse_list(&bag)?,
+                        None => vec![Bag::default()],
+                    };
+                }
+
+                if bags.len() > 1 && !args.explain {
+                    return Err("several bags are only compared with --explain".into());
+                }
+
+                Ok(Tuning::Day2(bags))
+            }
+            3 => {
+                let config = Config::load()?;
+
+                let rule = match args.gears.as_ref().or(config.gears.as_ref()) {
+                    Some(gears) => gears.parse()?,
+                    None => GearRule::default(),
+                };
+
+                let neighbourhood = match args
+                    .neighbourhood
+                    .as_ref()
+                    .or(config.neighbourhood.as_ref())
+                {
+                    Some(neighbourhood) => neighbourhood.parse()?,
+                    None => Neighbourhood::Eight,
+                };
+
+                let adjacency = Adjacency {
+                    neighbourhood,
+                    wrap: args.wrap || config.wrap,
+                };
+
+                Ok(Tuning::Day3(rule, adjacency))
+            }
+            _ => Ok(Tuning::Default),
+        }
+    }
+
+    /// The answer to a part along with warnings about input it skipped.
+    fn solve(
+        &self,
+        day: u8,
+        part: u8,
+        input: &PuzzleInput,
+    ) -> Result<(String, Vec<ParseError>), Box<dyn Error>> {
+        let answer = match self {
+            Tuning::Default => aoc::solve(day, part, &input.text)?,
+            Tuning::Day1(vocabulary) => {
+                let calibrated = day1::solve(part, &input.text, vocabulary)?;
+                return Ok((calibrated.sum.to_string(), calibrated.warnings));
+            }
+            Tuning::Day2(bags) => day2::solve(part, &input.text, &bags[0])?,
+            Tuning::Day3(rule, adjacency) => day3::solve(part, &input.text, rule, *adjacency)?,
+        };
+
+        Ok((answer, Vec::new()))
+    }
+}
+
+fn verify(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let days = match args {
+        [] => aoc::DAYS.to_vec(),
+        [flag, day] if flag == "--day" || flag == "-d" => vec![day.parse()?],
+        _ => return Err(USAGE.into()),
+    };
+
+    let checks = answers::verify(Path::new("."), &days)?;
+
+    print!("{}", Report(&checks));
+
+    let failures = checks.iter().filter(|c| !c.is_pass()).count();
+
+    if failures > 0 {
+        return Err(format!("{} of {} answers did not match", failures, checks.len()).into());
+    }
+
+    println!("all {} answers match", checks.len());
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct BenchArgs {
+    days: Vec<u8>,
+    input: Option<String>,
+    iterations: usize,
+    output: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+}
+
+impl BenchArgs {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut bench_args = BenchArgs {
+            days: aoc::DAYS.to_vec(),
+            input: None,
+            iterations: 10,
+            output: None,
+            baseline: None,
+            threshold: 10.0,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+            match arg.as_str() {
+                "--day" | "-d" => bench_args.days = vec![value()?.parse()?],
+                "--input" | "-i" => bench_args.input = Some(value()?.to_string()),
+                "--iterations" | "-n" => bench_args.iterations = value()?.parse()?,
+                "--output" | "-o" => bench_args.output = Some(value()?.to_string()),
+                "--baseline" | "-b" => bench_args.baseline = Some(value()?.to_string()),
+                "--threshold" | "-t" => bench_args.threshold = value()?.parse()?,
+                _ => return Err(format!("unknown argument {}\n{}", arg, USAGE).into()),
+            }
+        }
+
+        if bench_args.input.is_some() && bench_args.days.len() != 1 {
+            return Err("--input needs a single --day".into());
+        }
+
+        Ok(bench_args)
+    }
+}
+
+fn bench(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = BenchArgs::parse(args)?;
+
+    let mut results = Vec::new();
+
+    for &day in &args.days {
+        let path = args
+            .input
+            .clone()
+            .unwrap_or_else(|| input::default_path(day));
+        let input = input::read_input(day, &path)?;
+
+        let timings = aoc::bench(day, &input.label, &input.text, args.iterations)
+            .map_err(|e| error::with_file(e, &input.label))?;
+
+        results.extend(timings);
+    }
+
+    let report = BenchReport {
+        iterations: args.iterations,
+        results,
+    };
+
+    print!("{}", report.table());
+
+    if let Some(output) = &args.output {
+        fs::write(output, format!("{}\n", report.to_json()))?;
+    }
+
+    if let Some(baseline) = &args.baseline {
+        let baseline = BenchReport::parse(&read_to_string(baseline)?)?;
+        let comparisons = bench::compare(&report, &baseline, args.threshold);
+
+        println!();
+        print!("{}", bench::comparison_table(&comparisons));
+
+        let regressions = comparisons.iter().filter(|c| c.regressed).count();
+
+        if regressions > 0 {
+            return Err(format!(
+                "{} stages are more than {}% slower than the baseline",
+                regressions, args.threshold
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+fn fetch(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut config = Config::load()?;
+    let mut day: Option<u8> = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(value()?.parse()?),
+            "--base-url" => config.base_url = value()?.to_string(),
+            _ => return Err(format!("unknown argument {}\n{}", arg, USAGE).into()),
+        }
+    }
+
+    let day = day.ok_or("missing --day")?;
+    let client = Client::new(&config.base_url, config.session()?);
+
+    match fetch::fetch_input(&client, Path::new("."), day)? {
+        Fetched::Cached(path) => println!("{} is already cached", path.display()),
+        Fetched::Downloaded(path) => println!("saved {}", path.display()),
+    }
+
+    Ok(())
+}
+
+fn submit(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut config = Config::load()?;
+    let mut day: Option<u8> = None;
+    let mut part: Option<u8> = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(value()?.parse()?),
+            "--part" | "-p" => part = Some(value()?.parse()?),
+            "--base-url" => config.base_url = value()?.to_string(),
+            _ => return Err(format!("unknown argument {}\n{}", arg, USAGE).into()),
+        }
+    }
+
+    let day = day.ok_or("missing --day")?;
+    let part = part.ok_or("missing --part")?;
+
+    let path = input::default_path(day);
+    let input = input::read_input(day, &path)?;
+    let answer = aoc::solve(day, part, &input.text).map_err(|e| error::with_file(e, &path))?;
+
+    println!("day {} part {}: submitting {}", day, part, answer);
+
+    let client = Client::new(&config.base_url, config.session()?);
+    let attempt = submit::submit(&client, Path::new("."), day, part, &answer)?;
+
+    match attempt.verdict {
+        Verdict::Correct => {
+            println!("that's the right answer");
+            answers::register(Path::new("."), day, "input.txt", part, &answer)?;
+        }
+        Verdict::RateLimited => println!("answered too recently, wait {}s", attempt.wait),
+        verdict if verdict.is_wrong() => {
+            println!(
+                "wrong answer ({}), wait {}s before trying again",
+                verdict, attempt.wait
+            )
+        }
+        verdict => println!("website replied {}", verdict),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args().collect();
+
+    let result = match args.get(1).map(|s| s.as_str()) {
+        Some("run") => run(&args[2..]),
+        Some("verify") => verify(&args[2..]),
+        Some("bench") => bench(&args[2..]),
+        Some("fetch") => fetch(&args[2..]),
+        Some("submit") => submit(&args[2..]),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
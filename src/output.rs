@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::json::Json;
+
+/// One answer of a run, the stable schema of the machine-readable formats.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub duration_ns: u64,
+}
+
+impl Record {
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("day".into(), self.day.into()),
+            ("part".into(), self.part.into()),
+            ("input".into(), self.input.as_str().into()),
+            ("answer".into(), self.answer.as_str().into()),
+            ("duration_ns".into(), self.duration_ns.into()),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            f => Err(format!("unknown format {}, expected json, tsv or text", f).into()),
+        }
+    }
+}
+
+impl Format {
+    /// Renders all records: a JSON array, a TSV table with a header row or
+    /// one sentence per answer.
+    pub fn render(&self, records: &[Record]) -> String {
+        match self {
+            Format::Json => {
+                let records = Json::Array(records.iter().map(|r| r.to_json()).collect());
+                format!("{}\n", records)
+            }
+            Format::Tsv => {
+                let mut out = String::from("day\tpart\tinput\tanswer\tduration_ns\n");
+                for r in records {
+                    out.push_str(&format!(
+                        "{}\t{}\t{}\t{}\t{}\n",
+                        r.day,
+                        r.part,
+                        tsv_field(&r.input),
+                        tsv_field(&r.answer),
+                        r.duration_ns
+                    ));
+                }
+                out
+            }
+            Format::Text => records
+                .iter()
+                .map(|r| {
+                    format!(
+                        "day {} part {} ({}): {}\n",
+                        r.day, r.part, r.input, r.answer
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn tsv_field(s: &str) -> String {
+    s.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: 2,
+                input: "1/input.txt".to_string(),
+                answer: "54824".to_string(),
+                duration_ns: 1500,
+            },
+            Record {
+                day: 3,
+                part: 1,
+                input: "odd\tname\n\"x\".txt".to_string(),
+                answer: "4361".to_string(),
+                duration_ns: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_schema() {
+        let expected = concat!(
+            r#"[{"day":1,"part":2,"input":"1/input.txt","answer":"54824","duration_ns":1500},"#,
+            r#"{"day":3,"part":1,"input":"odd\tname\n\"x\".txt","answer":"4361","duration_ns":0}]"#,
+            "\n"
+        );
+
+        assert_eq!(Format::Json.render(&records()), expected);
+        assert_eq!(Format::Json.render(&[]), "[]\n");
+    }
+
+    #[test]
+    fn tsv_schema() {
+        let expected = concat!(
+            "day\tpart\tinput\tanswer\tduration_ns\n",
+            "1\t2\t1/input.txt\t54824\t1500\n",
+            "3\t1\todd name \"x\".txt\t4361\t0\n",
+        );
+
+        assert_eq!(Format::Tsv.render(&records()), expected);
+        assert_eq!(
+            Format::Tsv.render(&[]),
+            "day\tpart\tinput\tanswer\tduration_ns\n"
+        );
+    }
+
+    #[test]
+    fn text_lines() {
+        let text = Format::Text.render(&records()[..1]);
+        assert_eq!(text, "day 1 part 2 (1/input.txt): 54824\n");
+    }
+}
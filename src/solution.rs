@@ -0,0 +1,30 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::error::ParseError;
+
+/// A day of the puzzle, split into a parse stage and the two parts that
+/// work on the parsed input.
+pub trait Solution {
+    type Input;
+    type Output: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part_one(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>>;
+}
+
+/// Parses the input and runs the given part, returning the rendered answer.
+pub fn solve<S: Solution>(part: u8, input: &str) -> Result<String, Box<dyn Error>> {
+    let input = S::parse(input)?;
+
+    let answer = match part {
+        1 => S::part_one(&input)?,
+        2 => S::part_two(&input)?,
+        p => return Err(format!("invalid part {}", p).into()),
+    };
+
+    Ok(answer.to_string())
+}
@@ -0,0 +1,305 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::client::Client;
+use crate::YEAR;
+
+/// What the website said about a submitted answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Submitted too soon after the previous answer.
+    RateLimited,
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited => "rate-limited",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::RateLimited,
+            Verdict::AlreadySolved,
+            Verdict::Unknown,
+        ]
+        .into_iter()
+        .find(|v| v.name() == name)
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Reads the verdict and the enforced wait in seconds out of the answer page.
+pub fn parse_response(page: &str) -> (Verdict, u64) {
+    let text = page.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.contains("That's the right answer") {
+        (Verdict::Correct, 0)
+    } else if text.contains("That's not the right answer") {
+        let verdict = if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        };
+
+        (verdict, wrong_answer_wait(&text))
+    } else if text.contains("You gave an answer too recently") {
+        (Verdict::RateLimited, left_to_wait(&text))
+    } else if text.contains("Did you already complete it?") {
+        (Verdict::AlreadySolved, 0)
+    } else {
+        (Verdict::Unknown, 0)
+    }
+}
+
+// "please wait one minute before trying again" or "please wait 5 minutes ..."
+fn wrong_answer_wait(text: &str) -> u64 {
+    let Some((_, rest)) = text.split_once("please wait ") else {
+        return 60;
+    };
+
+    match rest.split_whitespace().next() {
+        Some("one") => 60,
+        Some(n) => n.parse::<u64>().map(|m| m * 60).unwrap_or(60),
+        None => 60,
+    }
+}
+
+// "You have 4m 33s left to wait." or "You have 39s left to wait."
+fn left_to_wait(text: &str) -> u64 {
+    let Some((_, rest)) = text.split_once("You have ") else {
+        return 60;
+    };
+    let Some((duration, _)) = rest.split_once(" left to wait") else {
+        return 60;
+    };
+
+    duration
+        .split_whitespace()
+        .map(|part| {
+            let n = part.trim_end_matches(char::is_alphabetic);
+            let unit = &part[n.len()..];
+            let n: u64 = n.parse().unwrap_or(0);
+            match unit {
+                "h" => n * 3600,
+                "m" => n * 60,
+                _ => n,
+            }
+        })
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    pub time: u64,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Seconds the website asked to wait before the next submission.
+    pub wait: u64,
+}
+
+/// Every submission of a day, kept in `N/submissions.txt` as
+/// `<unix time> <part> <answer> <verdict> <wait seconds>` lines.
+#[derive(Debug)]
+pub struct Attempts {
+    path: PathBuf,
+    pub entries: Vec<Attempt>,
+}
+
+impl Attempts {
+    pub fn load(root: &Path, day: u8) -> Result<Self, Box<dyn Error>> {
+        let path = root.join(day.to_string()).join("submissions.txt");
+
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{}: {}", path.display(), e).into()),
+        };
+
+        let mut entries = Vec::new();
+
+        for (n, line) in data.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let bad_line = || format!("{} line {}: malformed attempt", path.display(), n + 1);
+
+            let [time, part, answer, verdict, wait] =
+                line.split_whitespace().collect::<Vec<_>>()[..]
+            else {
+                return Err(bad_line().into());
+            };
+
+            entries.push(Attempt {
+                time: time.parse()?,
+                part: part.parse()?,
+                answer: answer.to_string(),
+                verdict: Verdict::from_name(verdict).ok_or_else(bad_line)?,
+                wait: wait.parse()?,
+            });
+        }
+
+        Ok(Attempts { path, entries })
+    }
+
+    /// Explains why `answer` should not be sent, if it is already known to be
+    /// pointless or the website would reject it for being too early.
+    pub fn refusal(&self, part: u8, answer: &str, now: u64) -> Option<String> {
+        let attempts: Vec<_> = self.entries.iter().filter(|a| a.part == part).collect();
+
+        if let Some(correct) = attempts.iter().find(|a| a.verdict == Verdict::Correct) {
+            return Some(format!(
+                "part {} was already solved with {}",
+                part, correct.answer
+            ));
+        }
+
+        if let Some(wrong) = attempts
+            .iter()
+            .find(|a| a.answer == answer && a.verdict.is_wrong())
+        {
+            return Some(format!(
+                "{} was already submitted and was {}",
+                answer, wrong.verdict
+            ));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |verdict: Verdict| {
+                attempts
+                    .iter()
+                    .filter(move |a| a.verdict == verdict)
+                    .filter_map(|a| a.answer.parse::<i128>().ok())
+            };
+
+            if let Some(high) = bound(Verdict::TooHigh).min().filter(|&high| value >= high) {
+                return Some(format!(
+                    "{} is not below {} which was too high",
+                    answer, high
+                ));
+            }
+
+            if let Some(low) = bound(Verdict::TooLow).max().filter(|&low| value <= low) {
+                return Some(format!("{} is not above {} which was too low", answer, low));
+            }
+        }
+
+        let ready_at = self
+            .entries
+            .iter()
+            .map(|a| a.time + a.wait)
+            .max()
+            .unwrap_or(0);
+
+        if ready_at > now {
+            return Some(format!(
+                "the website asked to wait {}s more",
+                ready_at - now
+            ));
+        }
+
+        None
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        writeln!(
+            file,
+            "{} {} {} {} {}",
+            attempt.time, attempt.part, attempt.answer, attempt.verdict, attempt.wait
+        )?;
+
+        self.entries.push(attempt);
+
+        Ok(())
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Posts an answer unless the local log already rules it out, and records the outcome.
+pub fn submit(
+    client: &Client,
+    root: &Path,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<Attempt, Box<dyn Error>> {
+    let mut attempts = Attempts::load(root, day)?;
+    let time = now();
+
+    if let Some(reason) = attempts.refusal(part, answer, time) {
+        return Err(format!("not submitting: {}", reason).into());
+    }
+
+    let level = part.to_string();
+    let response = client.post_form(
+        &format!("/{}/day/{}/answer", YEAR, day),
+        &[("level", &level), ("answer", answer)],
+    )?;
+
+    if response.status != 200 {
+        return Err(format!("submitting failed with status {}", response.status).into());
+    }
+
+    let (verdict, wait) = parse_response(&response.body);
+
+    let attempt = Attempt {
+        time,
+        part,
+        answer: answer.to_string(),
+        verdict,
+        wait,
+    };
+
+    attempts.record(attempt.clone())?;
+
+    Ok(attempt)
+}
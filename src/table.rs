@@ -0,0 +1,28 @@
+/// Renders rows as a plain text table with `|` separated, left aligned columns.
+pub fn render(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+
+    let mut out = String::new();
+
+    for row in std::iter::once(&header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &w)| format!("{:<w$}", cell, w = w))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    out
+}
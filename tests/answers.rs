@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use aoc::answers::{verify, Report};
+
+fn verify_day(day: u8) {
+    let checks = verify(Path::new(env!("CARGO_MANIFEST_DIR")), &[day]).unwrap();
+
+    assert!(!checks.is_empty(), "no answers registered for day {}", day);
+    assert!(checks.iter().all(|c| c.is_pass()), "\n{}", Report(&checks));
+}
+
+#[test]
+fn day1() {
+    verify_day(1);
+}
+
+#[test]
+fn day2() {
+    verify_day(2);
+}
+
+#[test]
+fn day3() {
+    verify_day(3);
+}
+
+#[test]
+fn day4() {
+    verify_day(4);
+}
+
+#[test]
+fn day5() {
+    verify_day(5);
+}
+
+#[test]
+fn day6() {
+    verify_day(6);
+}
+
+#[test]
+fn day7() {
+    verify_day(7);
+}
+
+#[test]
+fn day8() {
+    verify_day(8);
+}
+
+#[test]
+fn day9() {
+    verify_day(9);
+}
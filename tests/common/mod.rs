@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+/// Serves `responses` one connection at a time and sends back every raw request.
+pub fn stub_server(responses: Vec<String>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            tx.send(read_request(&mut stream)).unwrap();
+
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (base_url, rx)
+}
+
+pub fn response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn read_request(stream: &mut impl Read) -> String {
+    let mut raw = Vec::new();
+    let mut buf = [0; 1024];
+
+    loop {
+        let n = stream.read(&mut buf).unwrap();
+        raw.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&raw).to_string();
+
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|l| l.strip_prefix("Content-Length: "))
+                .map(|l| l.parse().unwrap())
+                .unwrap_or(0);
+
+            if body.len() >= length {
+                return text;
+            }
+        }
+
+        if n == 0 {
+            return text;
+        }
+    }
+}
+
+pub fn temp_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use std::fs;
+
+use aoc::client::Client;
+use aoc::fetch::{fetch_input, Fetched};
+
+use common::{response, stub_server, temp_root};
+
+#[test]
+fn downloads_once_then_uses_cache() {
+    let (base_url, requests) = stub_server(vec![response("200 OK", "1 2 3\n4\n")]);
+    let client = Client::new(&base_url, "secret");
+    let root = temp_root("fetch");
+
+    let fetched = fetch_input(&client, &root, 9).unwrap();
+    assert_eq!(fetched, Fetched::Downloaded(root.join("9/input.txt")));
+    assert_eq!(
+        fs::read_to_string(root.join("9/input.txt")).unwrap(),
+        "1 2 3\n4\n"
+    );
+
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("GET /2023/day/9/input HTTP/1.1\r\n"));
+    assert!(request.contains("Cookie: session=secret\r\n"));
+
+    // The stub only answers once, so a second download would fail
+    let fetched = fetch_input(&client, &root, 9).unwrap();
+    assert_eq!(fetched, Fetched::Cached(root.join("9/input.txt")));
+}
+
+#[test]
+fn decodes_chunked_bodies() {
+    let (base_url, _requests) = stub_server(vec![
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nRL\n\n\r\n3\r\nAAA\r\n0\r\n\r\n"
+            .to_string(),
+    ]);
+    let client = Client::new(&base_url, "secret");
+    let root = temp_root("chunked");
+
+    fetch_input(&client, &root, 8).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("8/input.txt")).unwrap(),
+        "RL\n\nAAA"
+    );
+}
+
+#[test]
+fn reports_bad_session() {
+    let (base_url, _requests) = stub_server(vec![response(
+        "400 Bad Request",
+        "Please log in to get your puzzle input",
+    )]);
+    let client = Client::new(&base_url, "expired");
+    let root = temp_root("unauthorized");
+
+    let err = fetch_input(&client, &root, 1).unwrap_err();
+
+    assert!(err.to_string().contains("status 400"));
+    assert!(!root.join("1/input.txt").exists());
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::fs;
+
+use aoc::client::Client;
+use aoc::submit::{parse_response, submit, Attempts, Verdict};
+
+use common::{response, stub_server, temp_root};
+
+fn page(article: &str) -> String {
+    format!(
+        "<html><body><main><article><p>{}</p></article></main></body></html>",
+        article
+    )
+}
+
+#[test]
+fn parses_answer_pages() {
+    let right = page("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.");
+    assert_eq!(parse_response(&right), (Verdict::Correct, 0));
+
+    let high = page("That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; please wait one minute before trying again.");
+    assert_eq!(parse_response(&high), (Verdict::TooHigh, 60));
+
+    let low = page("That's not the right answer; your answer is too low.  please wait 5 minutes before trying again.");
+    assert_eq!(parse_response(&low), (Verdict::TooLow, 300));
+
+    let wrong = page("That's not the right answer.  please wait one minute before trying again.");
+    assert_eq!(parse_response(&wrong), (Verdict::Wrong, 60));
+
+    let early = page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 33s left to wait.");
+    assert_eq!(parse_response(&early), (Verdict::RateLimited, 273));
+
+    let done = page("You don't seem to be solving the right level.  Did you already complete it?");
+    assert_eq!(parse_response(&done), (Verdict::AlreadySolved, 0));
+}
+
+#[test]
+fn records_attempts_and_refuses_known_wrong_answers() {
+    let body = page("That's not the right answer; your answer is too high.  please wait one minute before trying again.");
+    let (base_url, requests) = stub_server(vec![response("200 OK", &body)]);
+    let client = Client::new(&base_url, "secret");
+    let root = temp_root("submit");
+
+    let attempt = submit(&client, &root, 6, 1, "1000").unwrap();
+    assert_eq!(attempt.verdict, Verdict::TooHigh);
+    assert_eq!(attempt.wait, 60);
+
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("POST /2023/day/6/answer HTTP/1.1\r\n"));
+    assert!(request.ends_with("\r\n\r\nlevel=1&answer=1000"));
+
+    let log = fs::read_to_string(root.join("6/submissions.txt")).unwrap();
+    assert!(log.ends_with(" 1 1000 too-high 60\n"));
+
+    let attempts = Attempts::load(&root, 6).unwrap();
+    let later = attempt.time + 120;
+
+    assert!(attempts
+        .refusal(1, "1000", later)
+        .unwrap()
+        .contains("too-high"));
+    assert!(attempts
+        .refusal(1, "1200", later)
+        .unwrap()
+        .contains("too high"));
+    assert!(attempts
+        .refusal(1, "900", attempt.time)
+        .unwrap()
+        .contains("wait"));
+    assert_eq!(attempts.refusal(1, "900", later), None);
+    assert_eq!(attempts.refusal(2, "1000", later), None);
+
+    // Refused before reaching the server, which would not answer again
+    let err = submit(&client, &root, 6, 1, "1000").unwrap_err();
+    assert!(err.to_string().starts_with("not submitting"));
+}